@@ -0,0 +1,62 @@
+use super::*;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Set of `glMemoryBarrier` bits, combined with `|`.
+/// ```ignore
+/// ctx.memory_barrier(MemoryBarrier::SHADER_STORAGE | MemoryBarrier::VERTEX_ATTRIB_ARRAY);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryBarrier(pub GLbitfield);
+
+impl MemoryBarrier {
+    pub const VERTEX_ATTRIB_ARRAY: MemoryBarrier =
+        MemoryBarrier(GL_VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    pub const ELEMENT_ARRAY: MemoryBarrier = MemoryBarrier(GL_ELEMENT_ARRAY_BARRIER_BIT);
+    pub const UNIFORM: MemoryBarrier = MemoryBarrier(GL_UNIFORM_BARRIER_BIT);
+    pub const TEXTURE_FETCH: MemoryBarrier = MemoryBarrier(GL_TEXTURE_FETCH_BARRIER_BIT);
+    pub const SHADER_IMAGE_ACCESS: MemoryBarrier =
+        MemoryBarrier(GL_SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Indirect draw commands, see [`GraphicsContext::draw_indirect`]
+    pub const COMMAND: MemoryBarrier = MemoryBarrier(GL_COMMAND_BARRIER_BIT);
+    pub const PIXEL_BUFFER: MemoryBarrier = MemoryBarrier(GL_PIXEL_BUFFER_BARRIER_BIT);
+    pub const TEXTURE_UPDATE: MemoryBarrier = MemoryBarrier(GL_TEXTURE_UPDATE_BARRIER_BIT);
+    pub const BUFFER_UPDATE: MemoryBarrier = MemoryBarrier(GL_BUFFER_UPDATE_BARRIER_BIT);
+    pub const FRAMEBUFFER: MemoryBarrier = MemoryBarrier(GL_FRAMEBUFFER_BARRIER_BIT);
+    pub const TRANSFORM_FEEDBACK: MemoryBarrier = MemoryBarrier(GL_TRANSFORM_FEEDBACK_BARRIER_BIT);
+    pub const ATOMIC_COUNTER: MemoryBarrier = MemoryBarrier(GL_ATOMIC_COUNTER_BARRIER_BIT);
+    pub const SHADER_STORAGE: MemoryBarrier = MemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT);
+    pub const ALL: MemoryBarrier = MemoryBarrier(GL_ALL_BARRIER_BITS);
+
+    pub fn contains(self, other: MemoryBarrier) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for MemoryBarrier {
+    type Output = MemoryBarrier;
+
+    fn bitor(self, rhs: MemoryBarrier) -> MemoryBarrier {
+        MemoryBarrier(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MemoryBarrier {
+    fn bitor_assign(&mut self, rhs: MemoryBarrier) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl GraphicsContext {
+    /// Make shader writes to images and storage buffers visible to the following reads
+    /// of the given kinds, e.g. vertex fetches of a buffer filled by a compute shader.
+    ///
+    /// NOTE: ignored when `features.memory_barrier` is false.
+    pub fn memory_barrier(&mut self, barriers: MemoryBarrier) -> &mut Self {
+        if !self.features.memory_barrier {
+            log::warn!("Memory barriers are not supported by the GPU, ignoring memory_barrier");
+            return self;
+        }
+        unsafe { glMemoryBarrier(barriers.0) };
+        self
+    }
+}
@@ -5,6 +5,25 @@ pub enum BlendValue {
     SourceAlpha,
     DestinationColor,
     DestinationAlpha,
+    /// Color set with `GraphicsContext::set_blend_color`
+    ConstantColor,
+    /// Alpha of the color set with `GraphicsContext::set_blend_color`
+    ConstantAlpha,
+    /// Second fragment shader output, for dual-source blending like subpixel text.
+    /// Requires `features.dual_source_blend`.
+    Source1Color,
+    /// Alpha of the second fragment shader output, requires `features.dual_source_blend`
+    Source1Alpha,
+}
+
+impl BlendFactor {
+    fn uses_source1(self) -> bool {
+        matches!(
+            self,
+            BlendFactor::Value(BlendValue::Source1Color | BlendValue::Source1Alpha)
+                | BlendFactor::OneMinusValue(BlendValue::Source1Color | BlendValue::Source1Alpha)
+        )
+    }
 }
 
 /// Blend factors.
@@ -30,6 +49,12 @@ pub enum Equation {
     /// Subtracts source from destination. Source and destination are
     /// multiplied by blending parameters before subtraction.
     ReverseSubtract,
+    /// Component-wise minimum of source and destination.
+    /// Blending parameters are ignored.
+    Min,
+    /// Component-wise maximum of source and destination.
+    /// Blending parameters are ignored.
+    Max,
 }
 
 impl Default for Equation {
@@ -38,6 +63,19 @@ impl Default for Equation {
     }
 }
 
+/// How `GraphicsContext::composite` combines the overlay with the base texture.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlendMode {
+    /// Overlay drawn over base
+    Alpha,
+    /// base + overlay
+    Add,
+    /// base * overlay
+    Multiply,
+    /// 1 - (1 - base) * (1 - overlay)
+    Screen,
+}
+
 /// Pixel arithmetic description for blending operations.
 /// Will be used in an equation:
 /// `equation(sfactor * source_color, dfactor * destination_color)`
@@ -60,4 +98,9 @@ impl BlendState {
             dfactor,
         }
     }
+
+    /// Blends with the second fragment shader output
+    pub(crate) fn uses_source1(&self) -> bool {
+        self.sfactor.uses_source1() || self.dfactor.uses_source1()
+    }
 }
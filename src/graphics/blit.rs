@@ -0,0 +1,156 @@
+use super::*;
+
+const BLIT_VERTEX: &str = r#"
+attribute vec2 pos;
+varying vec2 uv;
+void main() {
+    uv = pos * 0.5 + 0.5;
+    gl_Position = vec4(pos, 0.0, 1.0);
+}
+"#;
+
+const BLIT_FRAGMENT: &str = r#"
+#ifdef GL_ES
+precision mediump float;
+#endif
+varying vec2 uv;
+uniform sampler2D tex;
+void main() {
+    gl_FragColor = texture2D(tex, uv);
+}
+"#;
+
+const COMPOSITE_FRAGMENT: &str = r#"
+#ifdef GL_ES
+precision mediump float;
+#endif
+varying vec2 uv;
+uniform sampler2D base;
+uniform sampler2D overlay;
+uniform int mode;
+void main() {
+    vec4 b = texture2D(base, uv);
+    vec4 o = texture2D(overlay, uv);
+    vec3 c = o.rgb;
+    if (mode == 1) {
+        c = b.rgb + o.rgb;
+    } else if (mode == 2) {
+        c = b.rgb * o.rgb;
+    } else if (mode == 3) {
+        c = 1.0 - (1.0 - b.rgb) * (1.0 - o.rgb);
+    }
+    gl_FragColor = vec4(mix(b.rgb, c, o.a), b.a);
+}
+"#;
+
+/// Pipeline and fullscreen quad bindings of the built-in shaders, created on first use
+pub(crate) struct BlitQuad {
+    pipeline: Pipeline,
+    bindings: Bindings,
+}
+
+impl BlitQuad {
+    fn new(ctx: &mut GraphicsContext, fragment: &str, meta: ShaderMeta) -> BlitQuad {
+        let num_images = meta.images.len();
+        let shader = Shader::new(ctx, BLIT_VERTEX, fragment, meta)
+            .expect("Built-in blit shader failed to compile");
+        let pipeline = Pipeline::new(
+            ctx,
+            &[BufferLayout::default()],
+            &[VertexAttribute::new("pos", VertexFormat::Float2)],
+            shader,
+        );
+
+        let vertices: [[f32; 2]; 4] = [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]];
+        let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+        let bindings = Bindings {
+            vertex_buffers: vec![Buffer::immutable(ctx, BufferType::VertexBuffer, &vertices)],
+            vertex_buffer_offsets: vec![],
+            index_buffer: Buffer::immutable(ctx, BufferType::IndexBuffer, &indices),
+            // the blitted textures change every call and are bound directly
+            images: (0..num_images).map(|_| Texture::empty()).collect(),
+        };
+
+        BlitQuad { pipeline, bindings }
+    }
+}
+
+impl GraphicsContext {
+    /// Draw `texture` stretched over the `dest` rectangle of the current pass, e.g. to inspect
+    /// the output of a render pass during development.
+    ///
+    /// Uses a built-in pipeline, so the previously applied pipeline has to be applied again.
+    /// Should be applied after begin_pass.
+    pub fn blit_texture_to_screen(&mut self, texture: &Texture, dest: Viewport) -> &mut Self {
+        if self.blit_quad.is_none() {
+            let meta = ShaderMeta {
+                uniforms: UniformBlockLayout { uniforms: vec![] },
+                images: vec!["tex".to_string()],
+            };
+            self.blit_quad = Some(BlitQuad::new(self, BLIT_FRAGMENT, meta));
+        }
+        let blit_quad = self.blit_quad.take().unwrap();
+
+        // unknown to the cache only after `set_viewports`, restore the full pass then
+        let viewport = self
+            .cache
+            .viewport
+            .or_else(|| self.pass_stack.last().map(|&(_, w, h)| (0, 0, w, h)));
+
+        self.apply_viewport(dest.x, dest.y, dest.w, dest.h);
+        self.apply_pipeline(&blit_quad.pipeline);
+        self.apply_bindings(&blit_quad.bindings);
+        self.cache.bind_texture(0, texture.texture);
+        self.draw(0, 6, 1);
+
+        if let Some((x, y, w, h)) = viewport {
+            self.apply_viewport(x, y, w, h);
+        }
+
+        self.blit_quad = Some(blit_quad);
+        self
+    }
+
+    /// Combine `overlay` over `base` into the color texture of `target` with a built-in shader,
+    /// e.g. to add a bloom texture to the scene. Overlay alpha weights the blend.
+    ///
+    /// Runs its own pass, call it outside of `begin_pass`/`end_render_pass`.
+    /// The previously applied pipeline has to be applied again.
+    pub fn composite(
+        &mut self,
+        base: &Texture,
+        overlay: &Texture,
+        mode: BlendMode,
+        target: &RenderPass,
+    ) -> &mut Self {
+        if self.composite_quad.is_none() {
+            let meta = ShaderMeta {
+                uniforms: UniformBlockLayout {
+                    uniforms: vec![UniformDesc::new("mode", UniformType::Int1)],
+                },
+                images: vec!["base".to_string(), "overlay".to_string()],
+            };
+            self.composite_quad = Some(BlitQuad::new(self, COMPOSITE_FRAGMENT, meta));
+        }
+        let composite_quad = self.composite_quad.take().unwrap();
+
+        let mode: i32 = match mode {
+            BlendMode::Alpha => 0,
+            BlendMode::Add => 1,
+            BlendMode::Multiply => 2,
+            BlendMode::Screen => 3,
+        };
+
+        self.begin_pass(*target, PassAction::Nothing);
+        self.apply_pipeline(&composite_quad.pipeline);
+        self.apply_bindings(&composite_quad.bindings);
+        self.cache.bind_texture(0, base.texture);
+        self.cache.bind_texture(1, overlay.texture);
+        self.apply_uniforms(&mode);
+        self.draw(0, 6, 1);
+        self.end_render_pass();
+
+        self.composite_quad = Some(composite_quad);
+        self
+    }
+}
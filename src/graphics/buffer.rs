@@ -1,8 +1,13 @@
 use super::*;
+use std::rc::Rc;
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BufferType {
     VertexBuffer,
     IndexBuffer,
+    /// `DrawIndirectCommand`s for `GraphicsContext::draw_indirect`
+    DrawIndirect,
+    /// Source of `Texture::update_from_pbo` uploads
+    PixelUnpack,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -16,9 +21,22 @@ fn gl_buffer_target(buffer_type: &BufferType) -> GLenum {
     match buffer_type {
         BufferType::VertexBuffer => GL_ARRAY_BUFFER,
         BufferType::IndexBuffer => GL_ELEMENT_ARRAY_BUFFER,
+        BufferType::DrawIndirect => GL_DRAW_INDIRECT_BUFFER,
+        BufferType::PixelUnpack => GL_PIXEL_UNPACK_BUFFER,
     }
 }
 
+/// Index buffers need to know their index type, other buffers must not have one
+fn check_index_type(buffer_type: BufferType, index_type: Option<IndexType>) {
+    assert_eq!(
+        buffer_type == BufferType::IndexBuffer,
+        index_type.is_some(),
+        "Index buffers need an index type, other buffers none (got {:?} with {:?})",
+        buffer_type,
+        index_type
+    );
+}
+
 fn gl_usage(usage: &BufferUsage) -> GLenum {
     match usage {
         BufferUsage::Immutable => GL_STATIC_DRAW,
@@ -44,6 +62,15 @@ impl IndexType {
         }
     }
 
+    /// Smallest type of at least 16 bits that fits `max_index`
+    pub fn for_max_index(max_index: u32) -> IndexType {
+        if max_index <= u16::MAX as u32 {
+            IndexType::Short
+        } else {
+            IndexType::Int
+        }
+    }
+
     pub fn size(self) -> u8 {
         match self {
             IndexType::Byte => 1,
@@ -53,12 +80,38 @@ impl IndexType {
     }
 }
 
+/// Error of [`Buffer::read_back`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BufferError {
+    /// `glGetBufferSubData` is missing, `features.get_buffer_sub_data` is false
+    ReadBackUnsupported,
+    /// The output slice is larger than the buffer
+    OutOfBounds { len: usize, size: usize },
+}
+
+impl Display for BufferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self) // Display the same way as Debug
+    }
+}
+
+impl Error for BufferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+/// Clones share the GL buffer, it is deleted once the last clone is dropped.
 #[derive(Clone, Debug)]
 pub struct Buffer {
     pub(crate) gl_buf: GLuint,
+    _handle: Rc<GlHandle>,
     pub(crate) buffer_type: BufferType,
     pub(crate) size: usize,
     pub(crate) index_type: Option<IndexType>,
+    pub(crate) usage: BufferUsage,
+    /// Persistently mapped memory of `*_mapped` buffers
+    pub(crate) mapped: Option<*mut u8>,
 }
 
 impl Buffer {
@@ -100,60 +153,183 @@ impl Buffer {
 
         Buffer {
             gl_buf,
+            _handle: Rc::new(GlHandle(GlObject::Buffer(gl_buf))),
             buffer_type,
             size,
             index_type,
+            usage: BufferUsage::Immutable,
+            mapped: None,
         }
     }
 
-    pub fn stream(ctx: &mut GraphicsContext, buffer_type: BufferType, size: usize) -> Buffer {
-        let index_type = if buffer_type == BufferType::IndexBuffer {
-            Some(IndexType::Short)
-        } else {
-            None
-        };
+    /// Buffer of `size` bytes updated every frame, `GL_STREAM_DRAW`.
+    /// `index_type` is required for `BufferType::IndexBuffer` and must be `None` otherwise.
+    pub fn stream(
+        ctx: &mut GraphicsContext,
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        size: usize,
+    ) -> Buffer {
+        check_index_type(buffer_type, index_type);
+        Self::allocate(ctx, buffer_type, index_type, BufferUsage::Stream, size)
+    }
 
+    pub fn index_stream(ctx: &mut GraphicsContext, index_type: IndexType, size: usize) -> Buffer {
+        Self::allocate(
+            ctx,
+            BufferType::IndexBuffer,
+            Some(index_type),
+            BufferUsage::Stream,
+            size,
+        )
+    }
+
+    /// Buffer of `size` bytes updated now and then rather than every frame, `GL_DYNAMIC_DRAW`.
+    /// Can grow with `ensure_capacity`, e.g. for a mesh whose vertex count changes.
+    /// `index_type` is required for `BufferType::IndexBuffer` and must be `None` otherwise.
+    pub fn new_dynamic(
+        ctx: &mut GraphicsContext,
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        size: usize,
+    ) -> Buffer {
+        check_index_type(buffer_type, index_type);
+        Self::allocate(ctx, buffer_type, index_type, BufferUsage::Dynamic, size)
+    }
+
+    fn allocate(
+        ctx: &mut GraphicsContext,
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        usage: BufferUsage,
+        size: usize,
+    ) -> Buffer {
         let gl_target = gl_buffer_target(&buffer_type);
-        let gl_usage = gl_usage(&BufferUsage::Stream);
         let mut gl_buf: u32 = 0;
 
         unsafe {
             glGenBuffers(1, &mut gl_buf as *mut _);
             ctx.cache.store_buffer_binding(gl_target);
             ctx.cache.bind_buffer(gl_target, gl_buf, None);
-            glBufferData(gl_target, size as _, std::ptr::null() as *const _, gl_usage);
+            glBufferData(
+                gl_target,
+                size as _,
+                std::ptr::null() as *const _,
+                gl_usage(&usage),
+            );
             ctx.cache.restore_buffer_binding(gl_target);
         }
 
         Buffer {
             gl_buf,
+            _handle: Rc::new(GlHandle(GlObject::Buffer(gl_buf))),
             buffer_type,
             size,
             index_type,
+            usage,
+            mapped: None,
         }
     }
 
-    pub fn index_stream(ctx: &mut GraphicsContext, index_type: IndexType, size: usize) -> Buffer {
-        let gl_target = gl_buffer_target(&BufferType::IndexBuffer);
-        let gl_usage = gl_usage(&BufferUsage::Stream);
+    /// Like `stream`, but the storage is persistently and coherently mapped:
+    /// `update` writes straight into GPU visible memory instead of calling `glBufferSubData`.
+    ///
+    /// The caller is responsible for not overwriting data the GPU is still reading,
+    /// e.g. by cycling through several buffers.
+    ///
+    /// NOTE: falls back to a regular `stream` buffer when `features.buffer_storage` is false.
+    pub fn stream_mapped(
+        ctx: &mut GraphicsContext,
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        size: usize,
+    ) -> Buffer {
+        check_index_type(buffer_type, index_type);
+        Self::mapped(ctx, buffer_type, index_type, size)
+    }
+
+    /// Like `index_stream`, but persistently mapped, see `stream_mapped`.
+    pub fn index_stream_mapped(
+        ctx: &mut GraphicsContext,
+        index_type: IndexType,
+        size: usize,
+    ) -> Buffer {
+        Self::mapped(ctx, BufferType::IndexBuffer, Some(index_type), size)
+    }
+
+    fn mapped(
+        ctx: &mut GraphicsContext,
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        size: usize,
+    ) -> Buffer {
+        if !ctx.features.buffer_storage {
+            return Self::stream(ctx, buffer_type, index_type, size);
+        }
+
+        let gl_target = gl_buffer_target(&buffer_type);
+        let flags = GL_MAP_WRITE_BIT | GL_MAP_PERSISTENT_BIT | GL_MAP_COHERENT_BIT;
         let mut gl_buf: u32 = 0;
 
-        unsafe {
+        let mapped = unsafe {
             glGenBuffers(1, &mut gl_buf as *mut _);
             ctx.cache.store_buffer_binding(gl_target);
             ctx.cache.bind_buffer(gl_target, gl_buf, None);
-            glBufferData(gl_target, size as _, std::ptr::null() as *const _, gl_usage);
+            glBufferStorage(
+                gl_target,
+                size as _,
+                std::ptr::null(),
+                flags | GL_DYNAMIC_STORAGE_BIT,
+            );
+            let ptr = glMapBufferRange(gl_target, 0, size as _, flags);
             ctx.cache.restore_buffer_binding(gl_target);
-        }
+            ptr as *mut u8
+        };
+        assert!(!mapped.is_null(), "Failed to map buffer storage");
 
         Buffer {
             gl_buf,
-            buffer_type: BufferType::IndexBuffer,
+            _handle: Rc::new(GlHandle(GlObject::Buffer(gl_buf))),
+            buffer_type,
             size,
-            index_type: Some(index_type),
+            index_type,
+            usage: BufferUsage::Stream,
+            mapped: Some(mapped),
+        }
+    }
+
+    /// Immutable index buffer drawing `num_quads` quads of 4 vertices each as 2 triangles,
+    /// `[0, 1, 2, 0, 2, 3]` for the first quad and so on.
+    ///
+    /// The index type is `Short` while the last vertex index fits into 16 bits and `Int` otherwise,
+    /// `draw` picks it up from the buffer.
+    pub fn quad_indices(ctx: &mut GraphicsContext, num_quads: usize) -> Buffer {
+        let max_index = (num_quads * 4).saturating_sub(1);
+        let indices =
+            (0..num_quads as u32).flat_map(|quad| [0, 1, 2, 0, 2, 3].map(|i| quad * 4 + i));
+
+        match IndexType::for_max_index(max_index as u32) {
+            IndexType::Short => {
+                let indices: Vec<u16> = indices.map(|i| i as u16).collect();
+                Self::immutable(ctx, BufferType::IndexBuffer, &indices)
+            }
+            _ => {
+                let indices: Vec<u32> = indices.collect();
+                Self::immutable(ctx, BufferType::IndexBuffer, &indices)
+            }
         }
     }
 
+    /// Type of the indices of an index buffer, `None` for other buffers
+    pub fn index_type(&self) -> Option<IndexType> {
+        self.index_type
+    }
+
+    /// Pointer to the persistently mapped memory, `None` for unmapped buffers.
+    pub fn mapped_ptr(&self) -> Option<*mut u8> {
+        self.mapped
+    }
+
     pub fn update<T>(&self, ctx: &mut GraphicsContext, data: &[T]) {
         if self.buffer_type == BufferType::IndexBuffer {
             assert!(self.index_type.is_some());
@@ -164,6 +340,11 @@ impl Buffer {
 
         assert!(size <= self.size);
 
+        if let Some(mapped) = self.mapped {
+            unsafe { std::ptr::copy_nonoverlapping(data.as_ptr() as *const u8, mapped, size) };
+            return;
+        }
+
         let gl_target = gl_buffer_target(&self.buffer_type);
         ctx.cache.store_buffer_binding(gl_target);
         ctx.cache
@@ -172,15 +353,113 @@ impl Buffer {
         ctx.cache.restore_buffer_binding(gl_target);
     }
 
+    /// Like `update`, but orphans the old storage with `glBufferData(NULL)` first,
+    /// so the driver doesn't wait for draws still reading the previous content.
+    /// Meant for `stream` buffers rewritten many times per frame.
+    pub fn orphan_and_update<T>(&self, ctx: &mut GraphicsContext, data: &[T]) {
+        if self.buffer_type == BufferType::IndexBuffer {
+            assert!(self.index_type == Some(IndexType::for_type::<T>()));
+        }
+        assert!(
+            self.mapped.is_none(),
+            "Persistently mapped buffers can't be orphaned"
+        );
+
+        let size = mem::size_of_val(data);
+        assert!(size <= self.size);
+
+        let gl_target = gl_buffer_target(&self.buffer_type);
+        ctx.cache.store_buffer_binding(gl_target);
+        ctx.cache
+            .bind_buffer(gl_target, self.gl_buf, self.index_type);
+        unsafe {
+            glBufferData(
+                gl_target,
+                self.size as _,
+                std::ptr::null() as *const _,
+                gl_usage(&self.usage),
+            );
+            glBufferSubData(gl_target, 0, size as _, data.as_ptr() as *const _);
+        }
+        ctx.cache.restore_buffer_binding(gl_target);
+    }
+
     /// Size of buffer in bytes
     pub fn size(&self) -> usize {
         self.size
     }
-}
 
-impl Drop for Buffer {
-    fn drop(&mut self) {
-        unsafe { glDeleteBuffers(1, &self.gl_buf as *const _) }
+    /// Reallocate the buffer storage with `new_size` bytes when it is smaller, no-op otherwise.
+    /// The previous content is discarded on growth, `update` the buffer afterwards.
+    /// Clones made earlier share the storage but keep reporting the old `size`.
+    pub fn ensure_capacity(&mut self, ctx: &mut GraphicsContext, new_size: usize) {
+        if new_size <= self.size {
+            return;
+        }
+        assert!(
+            self.mapped.is_none(),
+            "Persistently mapped buffers can't grow"
+        );
+
+        let gl_target = gl_buffer_target(&self.buffer_type);
+        ctx.cache.store_buffer_binding(gl_target);
+        ctx.cache
+            .bind_buffer(gl_target, self.gl_buf, self.index_type);
+        unsafe {
+            glBufferData(
+                gl_target,
+                new_size as _,
+                std::ptr::null() as *const _,
+                gl_usage(&self.usage),
+            );
+        }
+        ctx.cache.restore_buffer_binding(gl_target);
+        self.size = new_size;
+    }
+
+    /// Size in bytes of the buffer storage as reported by the driver.
+    /// Differs from `size` when allocating the storage failed, e.g. when out of memory.
+    pub fn gl_size(&self, ctx: &mut GraphicsContext) -> usize {
+        self.gl_parameter(ctx, GL_BUFFER_SIZE) as usize
+    }
+
+    /// Usage hint of the buffer storage as reported by the driver, e.g. `GL_STATIC_DRAW`
+    pub fn gl_usage(&self, ctx: &mut GraphicsContext) -> GLenum {
+        self.gl_parameter(ctx, GL_BUFFER_USAGE) as GLenum
+    }
+
+    /// Copy the first `out.len()` bytes of the buffer to `out`, e.g. to check the results
+    /// of GPU writes. Stalls until all pending writes to the buffer are finished.
+    pub fn read_back(&self, ctx: &mut GraphicsContext, out: &mut [u8]) -> Result<(), BufferError> {
+        if out.len() > self.size {
+            return Err(BufferError::OutOfBounds {
+                len: out.len(),
+                size: self.size,
+            });
+        }
+
+        if !ctx.features.get_buffer_sub_data {
+            return Err(BufferError::ReadBackUnsupported);
+        }
+
+        let gl_target = gl_buffer_target(&self.buffer_type);
+        ctx.cache.store_buffer_binding(gl_target);
+        ctx.cache
+            .bind_buffer(gl_target, self.gl_buf, self.index_type);
+        unsafe { glGetBufferSubData(gl_target, 0, out.len() as _, out.as_mut_ptr() as *mut _) };
+        ctx.cache.restore_buffer_binding(gl_target);
+        Ok(())
+    }
+
+    fn gl_parameter(&self, ctx: &mut GraphicsContext, pname: GLenum) -> GLint {
+        let gl_target = gl_buffer_target(&self.buffer_type);
+        let mut value = 0;
+        ctx.cache.store_buffer_binding(gl_target);
+        ctx.cache
+            .bind_buffer(gl_target, self.gl_buf, self.index_type);
+        unsafe { glGetBufferParameteriv(gl_target, pname, &mut value) };
+        ctx.cache.restore_buffer_binding(gl_target);
+        value
     }
 }
 
@@ -194,6 +473,11 @@ pub struct Bindings {
     /// vertex in 3d space, as well as `(u,v)` coordinates that map the vertex
     /// to some position in the corresponding `Texture`.
     pub vertex_buffers: Vec<Buffer>,
+    /// Byte offsets added to the attributes of the corresponding vertex buffer,
+    /// allowing several meshes to live in one shared buffer.
+    ///
+    /// Missing entries (including an empty `Vec`) mean offset 0.
+    pub vertex_buffer_offsets: Vec<i64>,
     /// Index buffer which instructs the GPU in which order to draw vertices
     /// from a vertex buffer, with each subsequent 3 indices forming a
     /// triangle.
@@ -11,16 +11,38 @@ pub(crate) struct GlCache {
     pub(crate) stored_index_type: Option<IndexType>,
     pub(crate) stored_vertex_buffer: GLuint,
     pub(crate) stored_texture: GLuint,
+    pub(crate) stored_texture_3d: GLuint,
     pub(crate) index_buffer: GLuint,
     pub(crate) index_type: Option<IndexType>,
     pub(crate) vertex_buffer: GLuint,
     pub(crate) textures: [GLuint; MAX_SHADERSTAGE_IMAGES],
+    pub(crate) textures_3d: [GLuint; MAX_SHADERSTAGE_IMAGES],
+    pub(crate) framebuffer: GLuint,
     pub(crate) cur_pipeline: Option<Pipeline>,
+    /// Program of the last `glUseProgram`
+    pub(crate) program: GLuint,
+    pub(crate) front_face: FrontFaceOrder,
     pub(crate) color_blend: Option<BlendState>,
     pub(crate) alpha_blend: Option<BlendState>,
+    pub(crate) blend_color: Color,
     pub(crate) stencil: Option<StencilState>,
     pub(crate) color_write: ColorMask,
     pub(crate) cull_face: CullFace,
+    pub(crate) depth_test: bool,
+    pub(crate) depth_func: Comparison,
+    pub(crate) depth_write: bool,
+    pub(crate) sample_coverage: Option<(f32, bool)>,
+    pub(crate) depth_write_offset: Option<(f32, f32)>,
+    /// `None` until the first pass, or when GL state is unknown
+    pub(crate) viewport: Option<(i32, i32, i32, i32)>,
+    pub(crate) scissor: Option<(i32, i32, i32, i32)>,
+    pub(crate) scissor_test: bool,
+    pub(crate) depth_clamp: bool,
+    pub(crate) dither: bool,
+    pub(crate) line_width: f32,
+    pub(crate) polygon_mode: PolygonMode,
+    pub(crate) program_point_size: bool,
+    pub(crate) color_output: bool,
     pub(crate) attributes: [Option<CachedAttribute>; MAX_VERTEX_ATTRIBUTES],
 }
 
@@ -38,7 +60,7 @@ impl GlCache {
                     glBindBuffer(target, buffer);
                 }
             }
-        } else {
+        } else if target == GL_ELEMENT_ARRAY_BUFFER {
             if self.index_buffer != buffer {
                 self.index_buffer = buffer;
                 unsafe {
@@ -46,13 +68,18 @@ impl GlCache {
                 }
             }
             self.index_type = index_type;
+        } else {
+            // other targets are bound only to create, update or draw from a buffer
+            unsafe {
+                glBindBuffer(target, buffer);
+            }
         }
     }
 
     pub(crate) fn store_buffer_binding(&mut self, target: GLenum) {
         if target == GL_ARRAY_BUFFER {
             self.stored_vertex_buffer = self.vertex_buffer;
-        } else {
+        } else if target == GL_ELEMENT_ARRAY_BUFFER {
             self.stored_index_buffer = self.index_buffer;
             self.stored_index_type = self.index_type;
         }
@@ -64,11 +91,45 @@ impl GlCache {
                 self.bind_buffer(target, self.stored_vertex_buffer, None);
                 self.stored_vertex_buffer = 0;
             }
-        } else {
+        } else if target == GL_ELEMENT_ARRAY_BUFFER {
             if self.stored_index_buffer != 0 {
                 self.bind_buffer(target, self.stored_index_buffer, self.stored_index_type);
                 self.stored_index_buffer = 0;
             }
+        } else if target == GL_PIXEL_UNPACK_BUFFER {
+            // while an unpack buffer is bound, texture upload pointers are read as buffer offsets
+            self.bind_buffer(target, 0, None);
+        }
+    }
+
+    pub(crate) fn use_program(&mut self, program: GLuint) {
+        if self.program != program {
+            unsafe { glUseProgram(program) };
+            self.program = program;
+        }
+    }
+
+    pub(crate) fn set_scissor_test(&mut self, enabled: bool) {
+        if self.scissor_test != enabled {
+            unsafe {
+                if enabled {
+                    glEnable(GL_SCISSOR_TEST);
+                } else {
+                    glDisable(GL_SCISSOR_TEST);
+                }
+            }
+            self.scissor_test = enabled;
+        }
+    }
+
+    pub(crate) fn set_front_face(&mut self, front_face: FrontFaceOrder) {
+        if self.front_face != front_face {
+            let mode = match front_face {
+                FrontFaceOrder::Clockwise => GL_CW,
+                FrontFaceOrder::CounterClockwise => GL_CCW,
+            };
+            unsafe { glFrontFace(mode) };
+            self.front_face = front_face;
         }
     }
 
@@ -82,6 +143,17 @@ impl GlCache {
         }
     }
 
+    /// Same as `bind_texture`, for the `GL_TEXTURE_3D` target of the unit
+    pub(crate) fn bind_texture_3d(&mut self, slot_index: usize, texture: GLuint) {
+        unsafe {
+            glActiveTexture(GL_TEXTURE0 + slot_index as GLuint);
+            if self.textures_3d[slot_index] != texture {
+                glBindTexture(GL_TEXTURE_3D, texture);
+                self.textures_3d[slot_index] = texture;
+            }
+        }
+    }
+
     pub(crate) fn store_texture_binding(&mut self, slot_index: usize) {
         self.stored_texture = self.textures[slot_index];
     }
@@ -90,6 +162,14 @@ impl GlCache {
         self.bind_texture(slot_index, self.stored_texture);
     }
 
+    pub(crate) fn store_texture_3d_binding(&mut self, slot_index: usize) {
+        self.stored_texture_3d = self.textures_3d[slot_index];
+    }
+
+    pub(crate) fn restore_texture_3d_binding(&mut self, slot_index: usize) {
+        self.bind_texture_3d(slot_index, self.stored_texture_3d);
+    }
+
     pub(crate) fn clear_buffer_bindings(&mut self) {
         self.bind_buffer(GL_ARRAY_BUFFER, 0, None);
         self.vertex_buffer = 0;
@@ -104,6 +184,9 @@ impl GlCache {
                 self.bind_texture(ix, 0);
                 self.textures[ix] = 0;
             }
+            if self.textures_3d[ix] != 0 {
+                self.bind_texture_3d(ix, 0);
+            }
         }
     }
 }
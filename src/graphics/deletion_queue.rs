@@ -0,0 +1,107 @@
+use super::*;
+use std::{cell::RefCell, collections::VecDeque};
+
+/// GL object released by a `Drop` impl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum GlObject {
+    Buffer(GLuint),
+    Texture(GLuint),
+}
+
+impl GlObject {
+    pub(crate) unsafe fn delete(self) {
+        match self {
+            GlObject::Buffer(buffer) => glDeleteBuffers(1, &buffer),
+            GlObject::Texture(texture) => glDeleteTextures(1, &texture),
+        }
+    }
+}
+
+/// Owner of a GL object shared by all clones of a `Buffer` or `Texture`,
+/// the object is deleted when the last clone is dropped.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct GlHandle(pub(crate) GlObject);
+
+impl Drop for GlHandle {
+    fn drop(&mut self) {
+        delete_object(self.0);
+    }
+}
+
+/// Objects dropped during the last `delay + 1` frames, oldest frame first
+struct DeletionQueue {
+    delay: usize,
+    frames: VecDeque<Vec<GlObject>>,
+}
+
+thread_local! {
+    // Drop impls have no access to the context, GL contexts are bound to a thread anyway
+    static DELETION_QUEUE: RefCell<Option<DeletionQueue>> = const { RefCell::new(None) };
+}
+
+/// Delete `object` right away, or once it's old enough when deferred deletion is enabled.
+pub(crate) fn delete_object(object: GlObject) {
+    let deferred = DELETION_QUEUE.with(|queue| match &mut *queue.borrow_mut() {
+        Some(queue) => {
+            queue.frames.back_mut().unwrap().push(object);
+            true
+        }
+        None => false,
+    });
+    if !deferred {
+        unsafe { object.delete() }
+    }
+}
+
+/// Disable deferred deletion, returning all pending objects.
+pub(crate) fn take_pending_deletions() -> Vec<GlObject> {
+    DELETION_QUEUE.with(|queue| {
+        queue
+            .borrow_mut()
+            .take()
+            .map(|queue| queue.frames.into_iter().flatten().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Start a new frame, deleting the objects dropped `delay` frames ago.
+pub(crate) fn next_frame() {
+    let expired = DELETION_QUEUE.with(|queue| {
+        let mut expired = vec![];
+        if let Some(queue) = &mut *queue.borrow_mut() {
+            queue.frames.push_back(vec![]);
+            while queue.frames.len() > queue.delay + 1 {
+                expired.extend(queue.frames.pop_front().unwrap());
+            }
+        }
+        expired
+    });
+    for object in expired {
+        unsafe { object.delete() }
+    }
+}
+
+impl GraphicsContext {
+    /// Keep GL objects of dropped `Buffer`s and `Texture`s alive for `frames` more
+    /// `commit_frame`s, so frames still in flight on the GPU don't stall or glitch on their deletion.
+    ///
+    /// `0` disables deferred deletion and deletes all pending objects right away.
+    pub fn set_deferred_deletion(&mut self, frames: usize) -> &mut Self {
+        if frames == 0 {
+            for object in take_pending_deletions() {
+                unsafe { object.delete() }
+            }
+            return self;
+        }
+        DELETION_QUEUE.with(|queue| match &mut *queue.borrow_mut() {
+            Some(queue) => queue.delay = frames,
+            queue @ None => {
+                *queue = Some(DeletionQueue {
+                    delay: frames,
+                    frames: VecDeque::from([vec![]]),
+                })
+            }
+        });
+        self
+    }
+}
@@ -55,9 +55,10 @@ use super::*;
 ///
 /// It is only possible to measure single query at once.
 ///
-/// On OpenGL/WebGL platforms implementation relies on [`EXT_disjoint_timer_query`] extension.
+/// Implementation relies on desktop GL 3.3 or the [`ARB_timer_query`] extension, GLES is not
+/// supported. Check with [`ElapsedQuery::is_supported(ctx)`](ElapsedQuery::is_supported).
 ///
-/// [`EXT_disjoint_timer_query`]: https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_disjoint_timer_query.txt
+/// [`ARB_timer_query`]: https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_timer_query.txt
 ///
 #[derive(Clone)]
 pub struct ElapsedQuery {
@@ -80,7 +81,7 @@ impl ElapsedQuery {
     ///
     /// Implemented as `glBeginQuery(GL_TIME_ELAPSED, ...)` on OpenGL/WebGL platforms.
     ///
-    /// Use [`ElapsedQuery::is_supported()`] to check if functionality is available and the method can be called.
+    /// Use [`ElapsedQuery::is_supported(ctx)`](ElapsedQuery::is_supported) to check if functionality is available and the method can be called.
     pub fn begin_query(&mut self) {
         if self.gl_query == 0 {
             unsafe { glGenQueries(1, &mut self.gl_query) };
@@ -104,19 +105,17 @@ impl ElapsedQuery {
     /// command submission. Use [`ElapsedQuery::is_available()`] to check if the result is
     /// available for retrieval.
     ///
-    /// Use [`ElapsedQuery::is_supported()`] to check if functionality is available and the method can be called.
+    /// Use [`ElapsedQuery::is_supported(ctx)`](ElapsedQuery::is_supported) to check if functionality is available and the method can be called.
     pub fn get_result(&self) -> u64 {
-        // let mut time: GLuint64 = 0;
-        // assert!(self.gl_query != 0);
-        // unsafe { glGetQueryObjectui64v(self.gl_query, GL_QUERY_RESULT, &mut time) };
-        // time
-        0
+        let mut time: GLuint64 = 0;
+        assert!(self.gl_query != 0);
+        unsafe { glGetQueryObjectui64v(self.gl_query, GL_QUERY_RESULT, &mut time) };
+        time
     }
 
     /// Reports whenever elapsed timer is supported and other methods can be invoked.
-    pub fn is_supported() -> bool {
-        unimplemented!();
-        //unsafe { sapp_is_elapsed_timer_supported() }
+    pub fn is_supported(ctx: &GraphicsContext) -> bool {
+        ctx.features.elapsed_query
     }
 
     /// Reports whenever result of submitted query is available for retrieval with
@@ -125,19 +124,17 @@ impl ElapsedQuery {
     /// Note that the result may be ready only couple frames later due to asynchrnous nature of GPU
     /// command submission.
     ///
-    /// Use [`ElapsedQuery::is_supported()`] to check if functionality is available and the method can be called.
+    /// Use [`ElapsedQuery::is_supported(ctx)`](ElapsedQuery::is_supported) to check if functionality is available and the method can be called.
     pub fn is_available(&self) -> bool {
-        // let mut available: GLint = 0;
+        let mut available: GLint = 0;
 
-        // // begin_query was not called yet
-        // if self.gl_query == 0 {
-        //     return false;
-        // }
-
-        //unsafe { glGetQueryObjectiv(self.gl_query, GL_QUERY_RESULT_AVAILABLE, &mut available) };
-        //available != 0
+        // begin_query was not called yet
+        if self.gl_query == 0 {
+            return false;
+        }
 
-        false
+        unsafe { glGetQueryObjectiv(self.gl_query, GL_QUERY_RESULT_AVAILABLE, &mut available) };
+        available != 0
     }
 }
 
@@ -147,3 +144,22 @@ impl Drop for ElapsedQuery {
         self.gl_query = 0;
     }
 }
+
+impl GraphicsContext {
+    /// Measure the GPU time of the work submitted by `f`.
+    /// The result of the returned query can be read a few frames later, see [`ElapsedQuery`].
+    ///
+    /// `f` is still run, but `None` is returned when `features.elapsed_query` is false.
+    pub fn time_gpu<F: FnOnce(&mut GraphicsContext)>(&mut self, f: F) -> Option<ElapsedQuery> {
+        if !self.features.elapsed_query {
+            f(self);
+            return None;
+        }
+
+        let mut query = ElapsedQuery::new();
+        query.begin_query();
+        f(self);
+        query.end_query();
+        Some(query)
+    }
+}
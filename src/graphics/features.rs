@@ -1,11 +1,271 @@
+use super::gl::*;
+use super::TextureFormat;
+
 pub struct Features {
     pub instancing: bool,
+    /// `glDrawElementsInstancedBaseInstance`, GL 4.2 or `ARB_base_instance`
+    pub base_instance: bool,
+    /// `GL_DEPTH_CLAMP`, GL 3.2 or `ARB_depth_clamp`
+    pub depth_clamp: bool,
+    /// `glViewportArrayv`, GL 4.1 or `ARB_viewport_array`
+    pub viewport_array: bool,
+    /// `glBeginConditionalRender`, GL 3.0
+    pub conditional_render: bool,
+    /// `GL_CLAMP_TO_BORDER`, desktop GL, GLES 3.2 or `EXT_texture_border_clamp`
+    pub clamp_to_border: bool,
+    /// `glBufferStorage`, GL 4.4 or `ARB_buffer_storage`
+    pub buffer_storage: bool,
+    /// `TextureFormat::DXT1`/`DXT5`, `EXT_texture_compression_s3tc`
+    pub s3tc: bool,
+    /// `TextureFormat::ETC2`, GLES 3.0, GL 4.3 or `ARB_ES3_compatibility`
+    pub etc2: bool,
+    /// `TextureFormat::RGBA8UI`/`R32UI`, GL 3.0 or GLES 3.0
+    pub integer_textures: bool,
+    /// `Texture::download` with `glGetTexImage`, desktop GL only
+    pub get_tex_image: bool,
+    /// `GraphicsContext::draw_indirect`, GL 4.0, GLES 3.1 or `ARB_draw_indirect`
+    pub draw_indirect: bool,
+    /// `GraphicsContext::multi_draw_indirect`, GL 4.3 or `ARB_multi_draw_indirect`
+    pub multi_draw_indirect: bool,
+    /// `BufferType::PixelUnpack`, GL 2.1 or GLES 3.0
+    pub pixel_buffer: bool,
+    /// `Texture3D` and `RenderPass::new_3d_slice`, GL 3.0 or GLES 3.0
+    pub texture_3d: bool,
+    /// `glGenVertexArrays`, GL 3.0, GLES 3.0, `ARB_vertex_array_object` or
+    /// `OES_vertex_array_object` on GLES2.
+    /// Without it vertex attributes are set on the client-side default state.
+    pub vertex_array_object: bool,
+    /// `GraphicsContext::set_line_width` above 1, not on core profiles or most GLES drivers
+    pub wide_lines: bool,
+    /// `GraphicsContext::set_polygon_mode`, desktop GL only
+    pub polygon_mode: bool,
+    /// `GraphicsContext::set_program_point_size`, desktop GL.
+    /// On GLES `gl_PointSize` is always used.
+    pub program_point_size: bool,
+    /// `GraphicsContext::memory_barrier`, GL 4.2, GLES 3.1 or `ARB_shader_image_load_store`
+    pub memory_barrier: bool,
+    /// `Buffer::read_back` with `glGetBufferSubData`, desktop GL only
+    pub get_buffer_sub_data: bool,
+    /// `GraphicsContext::clear_color_attachment` with `glClearBufferfv`, GL 3.0 or GLES 3.0
+    pub clear_buffer: bool,
+    /// `glDrawRangeElements` in `GraphicsContext::draw_range`, desktop GL or GLES 3.0
+    pub draw_range_elements: bool,
+    /// `BlendValue::Source1Color`/`Source1Alpha`, GL 3.3, `ARB_blend_func_extended`
+    /// or `EXT_blend_func_extended`
+    pub dual_source_blend: bool,
+    /// `ElapsedQuery`, desktop GL 3.3 or `ARB_timer_query`.
+    /// GLES only exposes the `EXT`-suffixed entry points, which are not loaded.
+    pub elapsed_query: bool,
+    /// `OcclusionQuery` with `GL_SAMPLES_PASSED`, desktop GL 1.5
+    pub occlusion_query: bool,
 }
 
 impl Features {
     pub fn from_gles2(is_gles2: bool) -> Self {
+        Self::from_info(is_gles2, &GlInfo::query())
+    }
+
+    pub(crate) fn from_info(is_gles2: bool, info: &GlInfo) -> Self {
         Features {
             instancing: !is_gles2,
+            base_instance: !info.gles
+                && (info.at_least(4, 2) || info.has_extension("GL_ARB_base_instance")),
+            depth_clamp: !info.gles
+                && (info.at_least(3, 2) || info.has_extension("GL_ARB_depth_clamp")),
+            viewport_array: !info.gles
+                && (info.at_least(4, 1) || info.has_extension("GL_ARB_viewport_array")),
+            conditional_render: !info.gles && info.at_least(3, 0),
+            clamp_to_border: !info.gles
+                || info.at_least(3, 2)
+                || info.has_extension("GL_EXT_texture_border_clamp"),
+            buffer_storage: !info.gles
+                && (info.at_least(4, 4) || info.has_extension("GL_ARB_buffer_storage")),
+            s3tc: info.has_extension("GL_EXT_texture_compression_s3tc"),
+            etc2: (info.gles && info.at_least(3, 0))
+                || (!info.gles && info.at_least(4, 3))
+                || info.has_extension("GL_ARB_ES3_compatibility"),
+            integer_textures: info.at_least(3, 0),
+            get_tex_image: !info.gles,
+            draw_indirect: (!info.gles && info.at_least(4, 0))
+                || (info.gles && info.at_least(3, 1))
+                || info.has_extension("GL_ARB_draw_indirect"),
+            multi_draw_indirect: !info.gles
+                && (info.at_least(4, 3) || info.has_extension("GL_ARB_multi_draw_indirect")),
+            pixel_buffer: (!info.gles && info.at_least(2, 1)) || info.at_least(3, 0),
+            texture_3d: info.at_least(3, 0),
+            vertex_array_object: info.at_least(3, 0)
+                || info.has_extension("GL_ARB_vertex_array_object")
+                || info.has_extension("GL_OES_vertex_array_object"),
+            wide_lines: info.max_line_width > 1.,
+            polygon_mode: !info.gles,
+            program_point_size: !info.gles,
+            memory_barrier: (!info.gles && info.at_least(4, 2))
+                || (info.gles && info.at_least(3, 1))
+                || info.has_extension("GL_ARB_shader_image_load_store"),
+            get_buffer_sub_data: !info.gles,
+            clear_buffer: info.at_least(3, 0),
+            draw_range_elements: !info.gles || info.at_least(3, 0),
+            dual_source_blend: (!info.gles && info.at_least(3, 3))
+                || info.has_extension("GL_ARB_blend_func_extended")
+                || info.has_extension("GL_EXT_blend_func_extended"),
+            elapsed_query: !info.gles
+                && (info.at_least(3, 3) || info.has_extension("GL_ARB_timer_query")),
+            occlusion_query: !info.gles && info.at_least(1, 5),
+        }
+    }
+
+    /// Whenever textures of `format` can be created
+    pub fn is_format_supported(&self, format: TextureFormat) -> bool {
+        match format {
+            TextureFormat::DXT1 | TextureFormat::DXT5 => self.s3tc,
+            TextureFormat::ETC2 => self.etc2,
+            TextureFormat::RGBA8UI | TextureFormat::R32UI => self.integer_textures,
+            _ => true,
+        }
+    }
+}
+
+/// Version and extensions of the current GL context
+pub(crate) struct GlInfo {
+    pub(crate) major: u32,
+    pub(crate) minor: u32,
+    pub(crate) gles: bool,
+    pub(crate) extensions: Vec<String>,
+    /// Upper bound of `GL_ALIASED_LINE_WIDTH_RANGE`
+    pub(crate) max_line_width: f32,
+}
+
+impl GlInfo {
+    pub(crate) fn query() -> GlInfo {
+        let version = unsafe { gl_string(glGetString(GL_VERSION)) };
+        let (major, minor, gles) = parse_version(&version);
+
+        let extensions = if major >= 3 {
+            let mut count = 0;
+            unsafe { glGetIntegerv(GL_NUM_EXTENSIONS, &mut count) };
+            (0..count.max(0) as GLuint)
+                .map(|i| unsafe { gl_string(glGetStringi(GL_EXTENSIONS, i)) })
+                .collect()
+        } else {
+            parse_extensions(&unsafe { gl_string(glGetString(GL_EXTENSIONS)) })
+        };
+
+        let mut line_width_range = [1.; 2];
+        unsafe { glGetFloatv(GL_ALIASED_LINE_WIDTH_RANGE, line_width_range.as_mut_ptr()) };
+
+        GlInfo {
+            major,
+            minor,
+            gles,
+            extensions,
+            max_line_width: line_width_range[1],
+        }
+    }
+
+    pub(crate) fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+
+    /// GLES2 exposes vertex array objects only through the `OES` suffixed entry points
+    pub(crate) fn oes_vertex_array_object(&self) -> bool {
+        self.gles && !self.at_least(3, 0) && self.has_extension("GL_OES_vertex_array_object")
+    }
+
+    pub(crate) fn has_extension(&self, name: &str) -> bool {
+        self.extensions.iter().any(|ext| ext == name)
+    }
+}
+
+unsafe fn gl_string(ptr: *const GLubyte) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    std::ffi::CStr::from_ptr(ptr as _)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// "4.6.0 NVIDIA 535.54" -> (4, 6, false), "OpenGL ES 3.2 Mesa" -> (3, 2, true)
+fn parse_version(version: &str) -> (u32, u32, bool) {
+    let gles = version.starts_with("OpenGL ES");
+    let number = version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or("");
+    let mut parts = number.split('.').map(|n| n.parse().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor, gles)
+}
+
+/// Space separated `GL_EXTENSIONS` string of GL 2 contexts
+fn parse_extensions(extensions: &str) -> Vec<String> {
+    extensions.split_whitespace().map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(version: &str, extensions: &str) -> GlInfo {
+        let (major, minor, gles) = parse_version(version);
+        GlInfo {
+            major,
+            minor,
+            gles,
+            extensions: parse_extensions(extensions),
+            max_line_width: 1.,
         }
     }
+
+    #[test]
+    fn parses_desktop_and_gles_versions() {
+        assert_eq!(parse_version("4.6.0 NVIDIA 535.54"), (4, 6, false));
+        assert_eq!(parse_version("3.3 (Core Profile) Mesa 23.1"), (3, 3, false));
+        assert_eq!(parse_version("OpenGL ES 3.2 Mesa 23.1"), (3, 2, true));
+        assert_eq!(parse_version("OpenGL ES 2.0"), (2, 0, true));
+        assert_eq!(parse_version(""), (0, 0, false));
+    }
+
+    #[test]
+    fn compares_versions() {
+        let info = info("3.3.0", "");
+        assert!(info.at_least(3, 0));
+        assert!(info.at_least(3, 3));
+        assert!(!info.at_least(3, 4));
+        assert!(!info.at_least(4, 0));
+    }
+
+    #[test]
+    fn matches_whole_extension_names() {
+        let info = info(
+            "2.1",
+            " GL_ARB_base_instance  GL_EXT_texture_compression_s3tc ",
+        );
+        assert!(info.has_extension("GL_ARB_base_instance"));
+        assert!(info.has_extension("GL_EXT_texture_compression_s3tc"));
+        assert!(!info.has_extension("GL_ARB_base"));
+        assert!(!info.has_extension(""));
+    }
+
+    #[test]
+    fn gates_features_on_version_and_extensions() {
+        let gl21 = Features::from_info(true, &info("2.1", "GL_ARB_base_instance"));
+        assert!(gl21.base_instance);
+        assert!(!gl21.texture_3d);
+
+        let gles3 = Features::from_info(false, &info("OpenGL ES 3.0", "GL_ARB_base_instance"));
+        assert!(!gles3.base_instance);
+        assert!(gles3.texture_3d);
+        assert!(!gles3.get_tex_image);
+        assert!(!gles3.elapsed_query);
+        assert!(!gles3.occlusion_query);
+
+        let gles_timer = info("OpenGL ES 3.0", "GL_EXT_disjoint_timer_query");
+        assert!(!Features::from_info(false, &gles_timer).elapsed_query);
+
+        let gl_timer = info("3.2", "GL_ARB_timer_query");
+        assert!(Features::from_info(false, &gl_timer).elapsed_query);
+    }
 }
@@ -28,6 +28,11 @@ pub type GLclampd = f64;
 
 pub const GL_INT_2_10_10_10_REV: u32 = 0x8D9F;
 pub const GL_PROGRAM_POINT_SIZE: u32 = 0x8642;
+pub const GL_ALIASED_LINE_WIDTH_RANGE: u32 = 0x846E;
+pub const GL_FRONT_AND_BACK: u32 = 0x0408;
+pub const GL_POINT: u32 = 0x1B00;
+pub const GL_LINE: u32 = 0x1B01;
+pub const GL_FILL: u32 = 0x1B02;
 pub const GL_STENCIL_ATTACHMENT: u32 = 0x8D20;
 pub const GL_DEPTH_ATTACHMENT: u32 = 0x8D00;
 pub const GL_COLOR_ATTACHMENT2: u32 = 0x8CE2;
@@ -45,16 +50,20 @@ pub const GL_TEXTURE_CUBE_MAP_POSITIVE_Z: u32 = 0x8519;
 pub const GL_TEXTURE_CUBE_MAP: u32 = 0x8513;
 pub const GL_FUNC_SUBTRACT: u32 = 0x800A;
 pub const GL_FUNC_REVERSE_SUBTRACT: u32 = 0x800B;
+pub const GL_MIN: u32 = 0x8007;
+pub const GL_MAX: u32 = 0x8008;
 pub const GL_CONSTANT_COLOR: u32 = 0x8001;
 pub const GL_DECR_WRAP: u32 = 0x8508;
 pub const GL_LINEAR_MIPMAP_LINEAR: u32 = 0x2703;
 pub const GL_ELEMENT_ARRAY_BUFFER: u32 = 0x8893;
 pub const GL_SHORT: u32 = 0x1402;
 pub const GL_DEPTH_TEST: u32 = 0x0B71;
+pub const GL_DEPTH_CLAMP: u32 = 0x864F;
 pub const GL_TEXTURE_CUBE_MAP_NEGATIVE_Y: u32 = 0x8518;
 pub const GL_LINK_STATUS: u32 = 0x8B82;
 pub const GL_TEXTURE_CUBE_MAP_POSITIVE_Y: u32 = 0x8517;
 pub const GL_SAMPLE_ALPHA_TO_COVERAGE: u32 = 0x809E;
+pub const GL_SAMPLE_COVERAGE: u32 = 0x80A0;
 pub const GL_RGBA16F: u32 = 0x881A;
 pub const GL_CONSTANT_ALPHA: u32 = 0x8003;
 pub const GL_READ_FRAMEBUFFER: u32 = 0x8CA8;
@@ -68,14 +77,23 @@ pub const GL_NEAREST_MIPMAP_NEAREST: u32 = 0x2700;
 pub const GL_UNSIGNED_SHORT_4_4_4_4: u32 = 0x8033;
 pub const GL_SRC_ALPHA_SATURATE: u32 = 0x0308;
 pub const GL_STREAM_DRAW: u32 = 0x88E0;
+pub const GL_MAP_WRITE_BIT: u32 = 0x0002;
+pub const GL_MAP_PERSISTENT_BIT: u32 = 0x0040;
+pub const GL_MAP_COHERENT_BIT: u32 = 0x0080;
+pub const GL_DYNAMIC_STORAGE_BIT: u32 = 0x0100;
 pub const GL_ONE: u32 = 1;
 pub const GL_NEAREST_MIPMAP_LINEAR: u32 = 0x2702;
 pub const GL_RGB10_A2: u32 = 0x8059;
 pub const GL_RGBA8: u32 = 0x8058;
 pub const GL_COLOR_ATTACHMENT1: u32 = 0x8CE1;
+pub const GL_MAX_COLOR_ATTACHMENTS: u32 = 0x8CDF;
+pub const GL_MAX_DRAW_BUFFERS: u32 = 0x8824;
+pub const GL_MAX_SAMPLES: u32 = 0x8D57;
 pub const GL_RGBA4: u32 = 0x8056;
 pub const GL_RGB8: u32 = 0x8051;
 pub const GL_ARRAY_BUFFER: u32 = 0x8892;
+pub const GL_DRAW_INDIRECT_BUFFER: u32 = 0x8F3F;
+pub const GL_PIXEL_UNPACK_BUFFER: u32 = 0x88EC;
 pub const GL_STENCIL: u32 = 0x1802;
 pub const GL_TEXTURE_2D: u32 = 0x0DE1;
 pub const GL_DEPTH: u32 = 0x1801;
@@ -88,6 +106,12 @@ pub const GL_DECR: u32 = 0x1E03;
 pub const GL_FRAGMENT_SHADER: u32 = 0x8B30;
 pub const GL_FLOAT: u32 = 0x1406;
 pub const GL_TEXTURE_MAX_LOD: u32 = 0x813B;
+pub const GL_TEXTURE_MAX_LEVEL: u32 = 0x813D;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+pub const GL_COMPRESSED_RGB8_ETC2: u32 = 0x9274;
+pub const GL_NUM_COMPRESSED_TEXTURE_FORMATS: u32 = 0x86A2;
+pub const GL_COMPRESSED_TEXTURE_FORMATS: u32 = 0x86A3;
 pub const GL_DEPTH_COMPONENT: u32 = 0x1902;
 pub const GL_ONE_MINUS_DST_ALPHA: u32 = 0x0305;
 pub const GL_COLOR: u32 = 0x1800;
@@ -96,6 +120,10 @@ pub const GL_TRIANGLES: u32 = 0x0004;
 pub const GL_UNSIGNED_BYTE: u32 = 0x1401;
 pub const GL_TEXTURE_MAG_FILTER: u32 = 0x2800;
 pub const GL_ONE_MINUS_CONSTANT_ALPHA: u32 = 0x8004;
+pub const GL_SRC1_ALPHA: u32 = 0x8589;
+pub const GL_SRC1_COLOR: u32 = 0x88F9;
+pub const GL_ONE_MINUS_SRC1_COLOR: u32 = 0x88FA;
+pub const GL_ONE_MINUS_SRC1_ALPHA: u32 = 0x88FB;
 pub const GL_NONE: u32 = 0;
 pub const GL_SRC_COLOR: u32 = 0x0300;
 pub const GL_BYTE: u32 = 0x1400;
@@ -117,6 +145,31 @@ pub const GL_TEXTURE_MIN_FILTER: u32 = 0x2801;
 pub const GL_LINEAR_MIPMAP_NEAREST: u32 = 0x2701;
 pub const GL_EXTENSIONS: u32 = 0x1F03;
 pub const GL_NO_ERROR: u32 = 0;
+pub const GL_VERTEX_ATTRIB_ARRAY_BARRIER_BIT: u32 = 0x00000001;
+pub const GL_ELEMENT_ARRAY_BARRIER_BIT: u32 = 0x00000002;
+pub const GL_UNIFORM_BARRIER_BIT: u32 = 0x00000004;
+pub const GL_TEXTURE_FETCH_BARRIER_BIT: u32 = 0x00000008;
+pub const GL_SHADER_IMAGE_ACCESS_BARRIER_BIT: u32 = 0x00000020;
+pub const GL_COMMAND_BARRIER_BIT: u32 = 0x00000040;
+pub const GL_PIXEL_BUFFER_BARRIER_BIT: u32 = 0x00000080;
+pub const GL_TEXTURE_UPDATE_BARRIER_BIT: u32 = 0x00000100;
+pub const GL_BUFFER_UPDATE_BARRIER_BIT: u32 = 0x00000200;
+pub const GL_FRAMEBUFFER_BARRIER_BIT: u32 = 0x00000400;
+pub const GL_TRANSFORM_FEEDBACK_BARRIER_BIT: u32 = 0x00000800;
+pub const GL_ATOMIC_COUNTER_BARRIER_BIT: u32 = 0x00001000;
+pub const GL_SHADER_STORAGE_BARRIER_BIT: u32 = 0x00002000;
+pub const GL_ALL_BARRIER_BITS: u32 = 0xFFFFFFFF;
+pub const GL_BUFFER_SIZE: u32 = 0x8764;
+pub const GL_BUFFER_USAGE: u32 = 0x8765;
+pub const GL_STENCIL_WRITEMASK: u32 = 0x0B98;
+pub const GL_STENCIL_BACK_WRITEMASK: u32 = 0x8CA5;
+pub const GL_INVALID_ENUM: u32 = 0x0500;
+pub const GL_INVALID_VALUE: u32 = 0x0501;
+pub const GL_INVALID_OPERATION: u32 = 0x0502;
+pub const GL_STACK_OVERFLOW: u32 = 0x0503;
+pub const GL_STACK_UNDERFLOW: u32 = 0x0504;
+pub const GL_OUT_OF_MEMORY: u32 = 0x0505;
+pub const GL_INVALID_FRAMEBUFFER_OPERATION: u32 = 0x0506;
 pub const GL_REPLACE: u32 = 0x1E01;
 pub const GL_KEEP: u32 = 0x1E00;
 pub const GL_CCW: u32 = 0x0901;
@@ -132,10 +185,15 @@ pub const GL_UNSIGNED_INT: u32 = 0x1405;
 pub const GL_UNSIGNED_SHORT: u32 = 0x1403;
 pub const GL_NEAREST: u32 = 0x2600;
 pub const GL_SCISSOR_TEST: u32 = 0x0C11;
+pub const GL_SCISSOR_BOX: u32 = 0x0C10;
+pub const GL_VIEWPORT: u32 = 0x0BA2;
 pub const GL_LEQUAL: u32 = 0x0203;
 pub const GL_STENCIL_TEST: u32 = 0x0B90;
 pub const GL_DITHER: u32 = 0x0BD0;
 pub const GL_DEPTH_COMPONENT16: u32 = 0x81A5;
+pub const GL_DEPTH_COMPONENT24: u32 = 0x81A6;
+pub const GL_DEPTH24_STENCIL8: u32 = 0x88F0;
+pub const GL_DEPTH_STENCIL_ATTACHMENT: u32 = 0x821A;
 pub const GL_EQUAL: u32 = 0x0202;
 pub const GL_FRAMEBUFFER: u32 = 0x8D40;
 pub const GL_RGB5: u32 = 0x8050;
@@ -145,8 +203,10 @@ pub const GL_SRC_ALPHA: u32 = 0x0302;
 pub const GL_INCR_WRAP: u32 = 0x8507;
 pub const GL_LESS: u32 = 0x0201;
 pub const GL_MULTISAMPLE: u32 = 0x809D;
+pub const GL_SAMPLES: u32 = 0x80A9;
 pub const GL_FRAMEBUFFER_BINDING: u32 = 0x8CA6;
 pub const GL_BACK: u32 = 0x0405;
+pub const GL_BACK_LEFT: u32 = 0x0402;
 pub const GL_ALWAYS: u32 = 0x0207;
 pub const GL_FUNC_ADD: u32 = 0x8006;
 pub const GL_ONE_MINUS_DST_COLOR: u32 = 0x0307;
@@ -174,6 +234,7 @@ pub const GL_DST_ALPHA: u32 = 0x0304;
 pub const GL_RGB5_A1: u32 = 0x8057;
 pub const GL_GREATER: u32 = 0x0204;
 pub const GL_POLYGON_OFFSET_FILL: u32 = 0x8037;
+pub const GL_POLYGON_OFFSET_LINE: u32 = 0x2A02;
 pub const GL_TRUE: u32 = 1;
 pub const GL_NEVER: u32 = 0x0200;
 pub const GL_POINTS: u32 = 0x0000;
@@ -235,15 +296,22 @@ pub const GL_MAX_VERTEX_ATTRIBS: u32 = 0x8869;
 pub const GL_CLAMP_TO_BORDER: u32 = 0x812D;
 pub const GL_TEXTURE_BORDER_COLOR: u32 = 0x1004;
 pub const GL_UNPACK_ALIGNMENT: u32 = 3317;
+pub const GL_PACK_ALIGNMENT: u32 = 3333;
 pub const GL_TEXTURE_SWIZZLE_R: u32 = 36418;
 pub const GL_TEXTURE_SWIZZLE_G: u32 = 36419;
 pub const GL_TEXTURE_SWIZZLE_B: u32 = 36420;
 pub const GL_TEXTURE_SWIZZLE_A: u32 = 36421;
 pub const GL_TEXTURE_SWIZZLE_RGBA: u32 = 36422;
 pub const GL_DRAW_FRAMEBUFFER_BINDING: u32 = 36006;
+pub const GL_READ_FRAMEBUFFER_BINDING: u32 = 0x8CAA;
 pub const GL_TIME_ELAPSED: u32 = 35007;
 pub const GL_QUERY_RESULT: u32 = 34918;
 pub const GL_QUERY_RESULT_AVAILABLE: u32 = 34919;
+pub const GL_SAMPLES_PASSED: u32 = 0x8914;
+pub const GL_QUERY_WAIT: u32 = 0x8E13;
+pub const GL_QUERY_NO_WAIT: u32 = 0x8E14;
+pub const GL_QUERY_BY_REGION_WAIT: u32 = 0x8E15;
+pub const GL_QUERY_BY_REGION_NO_WAIT: u32 = 0x8E16;
 pub const GL_VENDOR: u32 = 0x1F00;
 pub const GL_VERSION: u32 = 0x1F02;
 
@@ -328,6 +396,7 @@ macro_rules! gl_loader {
 
 gl_loader!(
     fn glGetString(name: GLenum) -> *const GLubyte,
+    fn glGetStringi(name: GLenum, index: GLuint) -> *const GLubyte,
     fn glFramebufferTextureLayer(
         target: GLenum,
         attachment: GLenum,
@@ -373,6 +442,7 @@ gl_loader!(
         infoLog: *mut GLchar
     ) -> (),
     fn glGetAttribLocation(program: GLuint, name: *const GLchar) -> GLint,
+    fn glBindAttribLocation(program: GLuint, index: GLuint, name: *const GLchar) -> (),
     fn glDisableVertexAttribArray(index: GLuint) -> (),
     fn glDeleteShader(shader: GLuint) -> (),
     fn glDeleteProgram(program: GLuint) -> (),
@@ -394,6 +464,13 @@ gl_loader!(
         size: GLsizeiptr,
         data: *const ::std::os::raw::c_void
     ) -> (),
+    fn glClearBufferfv(buffer: GLenum, drawbuffer: GLint, value: *const GLfloat) -> (),
+    fn glGetBufferSubData(
+        target: GLenum,
+        offset: GLintptr,
+        size: GLsizeiptr,
+        data: *mut ::std::os::raw::c_void
+    ) -> (),
     fn glGenBuffers(n: GLsizei, buffers: *mut GLuint) -> (),
     fn glCheckFramebufferStatus(target: GLenum) -> GLenum,
     fn glFramebufferRenderbuffer(
@@ -412,6 +489,17 @@ gl_loader!(
         imageSize: GLsizei,
         data: *const GLvoid
     ) -> (),
+    fn glCompressedTexSubImage2D(
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        imageSize: GLsizei,
+        data: *const GLvoid
+    ) -> (),
     fn glCompressedTexImage3D(
         target: GLenum,
         level: GLint,
@@ -462,7 +550,16 @@ gl_loader!(
         height: GLsizei
     ) -> (),
     fn glPolygonOffset(factor: GLfloat, units: GLfloat) -> (),
+    fn glSampleCoverage(value: GLfloat, invert: GLboolean) -> (),
     fn glDrawElements(mode: GLenum, count: GLsizei, type_: GLenum, indices: *const GLvoid) -> (),
+    fn glDrawRangeElements(
+        mode: GLenum,
+        start: GLuint,
+        end: GLuint,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const GLvoid
+    ) -> (),
     fn glDeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint) -> (),
     fn glBlendEquationSeparate(modeRGB: GLenum, modeAlpha: GLenum) -> (),
     fn glDeleteTextures(n: GLsizei, textures: *const GLuint) -> (),
@@ -513,6 +610,7 @@ gl_loader!(
     ) -> (),
     fn glCreateProgram() -> GLuint,
     fn glViewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) -> (),
+    fn glViewportArrayv(first: GLuint, count: GLsizei, v: *const GLfloat) -> (),
     fn glDeleteBuffers(n: GLsizei, buffers: *const GLuint) -> (),
     fn glDrawArrays(mode: GLenum, first: GLint, count: GLsizei) -> (),
     fn glDrawElementsInstanced(
@@ -522,6 +620,26 @@ gl_loader!(
         indices: *const ::std::os::raw::c_void,
         instancecount: GLsizei
     ) -> (),
+    fn glDrawElementsInstancedBaseInstance(
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const ::std::os::raw::c_void,
+        instancecount: GLsizei,
+        baseinstance: GLuint
+    ) -> (),
+    fn glDrawElementsIndirect(
+        mode: GLenum,
+        type_: GLenum,
+        indirect: *const ::std::os::raw::c_void
+    ) -> (),
+    fn glMultiDrawElementsIndirect(
+        mode: GLenum,
+        type_: GLenum,
+        indirect: *const ::std::os::raw::c_void,
+        drawcount: GLsizei,
+        stride: GLsizei
+    ) -> (),
     fn glVertexAttribPointer(
         index: GLuint,
         size: GLint,
@@ -535,6 +653,9 @@ gl_loader!(
     fn glBindBuffer(target: GLenum, buffer: GLuint) -> (),
     fn glBindVertexArray(array: GLuint) -> (),
     fn glDeleteVertexArrays(n: GLsizei, arrays: *const GLuint) -> (),
+    fn glBindVertexArrayOES(array: GLuint) -> (),
+    fn glDeleteVertexArraysOES(n: GLsizei, arrays: *const GLuint) -> (),
+    fn glGenVertexArraysOES(n: GLsizei, arrays: *mut GLuint) -> (),
     fn glDepthMask(flag: GLboolean) -> (),
     fn glDrawArraysInstanced(
         mode: GLenum,
@@ -551,6 +672,19 @@ gl_loader!(
         data: *const ::std::os::raw::c_void,
         usage: GLenum
     ) -> (),
+    fn glBufferStorage(
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const ::std::os::raw::c_void,
+        flags: GLbitfield
+    ) -> (),
+    fn glMapBufferRange(
+        target: GLenum,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield
+    ) -> *mut ::std::os::raw::c_void,
+    fn glUnmapBuffer(target: GLenum) -> GLboolean,
     fn glBlendFuncSeparate(
         sfactorRGB: GLenum,
         dfactorRGB: GLenum,
@@ -558,8 +692,15 @@ gl_loader!(
         dfactorAlpha: GLenum
     ) -> (),
     fn glTexParameteri(target: GLenum, pname: GLenum, param: GLint) -> (),
+    fn glGenerateMipmap(target: GLenum) -> (),
     fn glGetIntegerv(pname: GLenum, params: *mut GLint) -> (),
+    fn glMemoryBarrier(barriers: GLbitfield) -> (),
+    fn glGetBufferParameteriv(target: GLenum, pname: GLenum, params: *mut GLint) -> (),
+    fn glGetFloatv(pname: GLenum, params: *mut GLfloat) -> (),
+    fn glLineWidth(width: GLfloat) -> (),
+    fn glPolygonMode(face: GLenum, mode: GLenum) -> (),
     fn glEnable(cap: GLenum) -> (),
+    fn glIsEnabled(cap: GLenum) -> GLboolean,
     fn glBlitFramebuffer(
         srcX0: GLint,
         srcY0: GLint,
@@ -608,6 +749,13 @@ gl_loader!(
     fn glFrontFace(mode: GLenum) -> (),
     fn glCullFace(mode: GLenum) -> (),
     fn glGenTextures(n: GLsizei, textures: *mut GLuint) -> (),
+    fn glGetTexImage(
+        target: GLenum,
+        level: GLint,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut GLvoid
+    ) -> (),
     fn glReadPixels(
         x: GLint,
         y: GLint,
@@ -623,6 +771,8 @@ gl_loader!(
     fn glGenQueries(n: GLsizei, ids: *mut GLuint) -> (),
     fn glGetQueryObjectiv(id: GLuint, pname: GLenum, params: *mut GLint) -> (),
     fn glGetQueryObjectui64v(id: GLuint, pname: GLenum, params: *mut GLuint64) -> (),
+    fn glBeginConditionalRender(id: GLuint, mode: GLenum) -> (),
+    fn glEndConditionalRender() -> (),
     fn glFlush() -> (),
     fn glFinish() -> ()
 );
@@ -0,0 +1,50 @@
+use super::*;
+
+/// Immutable vertex and index buffers packed together with their `Bindings`.
+/// ```ignore
+/// let mesh = Mesh::new(ctx, &vertices, &[0u16, 1, 2, 0, 2, 3], vec![texture]);
+///
+/// ctx.apply_pipeline(&pipeline);
+/// mesh.draw(ctx);
+/// ```
+pub struct Mesh {
+    bindings: Bindings,
+    num_elements: i32,
+}
+
+impl Mesh {
+    pub fn new<V, I>(
+        ctx: &mut GraphicsContext,
+        vertices: &[V],
+        indices: &[I],
+        images: Vec<Texture>,
+    ) -> Mesh {
+        let vertex_buffer = Buffer::immutable(ctx, BufferType::VertexBuffer, vertices);
+        let index_buffer = Buffer::immutable(ctx, BufferType::IndexBuffer, indices);
+
+        Mesh {
+            bindings: Bindings {
+                vertex_buffers: vec![vertex_buffer],
+                vertex_buffer_offsets: vec![],
+                index_buffer,
+                images,
+            },
+            num_elements: indices.len() as i32,
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Number of indices drawn by `draw`
+    pub fn num_elements(&self) -> i32 {
+        self.num_elements
+    }
+
+    /// Apply the mesh bindings and draw all of its indices with the currently applied pipeline.
+    pub fn draw(&self, ctx: &mut GraphicsContext) {
+        ctx.apply_bindings(&self.bindings);
+        ctx.draw(0, self.num_elements, 1);
+    }
+}
@@ -1,48 +1,130 @@
-use std::{ffi::CString, mem};
+use std::{cell::Cell, ffi::CString, mem};
 
+pub mod barrier;
 pub mod blend;
+mod blit;
 pub mod buffer;
 pub mod cache;
+pub mod color;
+mod deletion_queue;
 pub mod elspsed_query;
 pub mod features;
 pub mod gl;
+pub mod mesh;
+pub mod occlusion_query;
 pub mod pass;
 pub mod pipeline;
 pub mod shader;
+mod slab;
+#[cfg(feature = "sprite_batch")]
+pub mod sprite_batch;
 pub mod stencil;
 mod texture;
+mod texture_3d;
 mod transmute;
 pub mod uniform;
 
 use blend::*;
+use blit::*;
 use buffer::*;
 use cache::*;
+use color::*;
+use deletion_queue::*;
 use features::*;
 use gl::*;
+use occlusion_query::*;
 use pass::*;
 use pipeline::*;
 use shader::*;
+use slab::*;
 use stencil::*;
 use uniform::*;
 
 use std::{error::Error, fmt::Display};
 pub use texture::{FilterMode, Texture, TextureAccess, TextureFormat, TextureParams, TextureWrap};
+pub use texture_3d::Texture3D;
 
 pub type ColorMask = (bool, bool, bool, bool);
 pub const MAX_VERTEX_ATTRIBUTES: usize = 16;
 pub const MAX_SHADERSTAGE_IMAGES: usize = 12;
 
+/// Rectangle in framebuffer pixels, origin at the bottom left.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Viewport {
+    #[inline]
+    pub const fn new(x: i32, y: i32, w: i32, h: i32) -> Viewport {
+        Viewport { x, y, w, h }
+    }
+}
+
 pub struct GraphicsContext {
-    shaders: Vec<ShaderInternal>,
-    pipelines: Vec<PipelineInternal>,
-    passes: Vec<RenderPassInternal>,
+    shaders: Slab<ShaderInternal>,
+    pipelines: Slab<PipelineInternal>,
+    passes: Slab<RenderPassInternal>,
+    /// Framebuffer and size of every pass begun but not ended yet, innermost last
+    pass_stack: Vec<(GLuint, i32, i32)>,
     default_framebuffer: GLuint,
+    /// 0 without vertex array object support
+    vao: GLuint,
+    /// `vao` was created with the `OES_vertex_array_object` entry points
+    vao_oes: bool,
+    /// GLES context, the default framebuffer draw buffer is `GL_BACK` instead of `GL_BACK_LEFT`
+    gles: bool,
     cache: GlCache,
+    blit_quad: Option<BlitQuad>,
+    composite_quad: Option<BlitQuad>,
+    last_draw: Cell<DrawStatus>,
+    /// Index count of the buffer in the last applied `Bindings`, checked by `try_draw`
+    bound_index_elements: Option<usize>,
+    /// Scissor rect re-applied by every `begin_pass`
+    persistent_scissor: Option<Viewport>,
 
     pub(crate) features: Features,
     pub(crate) window: Option<*mut glfw::Window>,
 }
 
+/// How the GPU handled the last draw call, see [`GraphicsContext::last_draw_status`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DrawStatus {
+    Drawn,
+    /// Instancing is not supported, the geometry was drawn once per instance in a loop
+    InstancingEmulated,
+    /// The draw call needs a feature the GPU doesn't support and was dropped
+    Skipped,
+}
+
+/// Error of [`GraphicsContext::try_draw`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DrawError {
+    /// No `Bindings` were applied, so there is no index buffer to draw from
+    NoIndexBuffer,
+    /// `base..base + count` is negative or past the end of the bound index buffer
+    OutOfRange {
+        base: i32,
+        count: i32,
+        elements: usize,
+    },
+}
+
+impl Display for DrawError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self) // Display the same way as Debug
+    }
+}
+
+impl Error for DrawError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
 impl GraphicsContext {
     pub fn new(is_gles2: bool) -> GraphicsContext {
         unsafe {
@@ -51,17 +133,35 @@ impl GraphicsContext {
                 GL_FRAMEBUFFER_BINDING,
                 &mut default_framebuffer as *mut _ as *mut _,
             );
+            let info = GlInfo::query();
+            let features = Features::from_info(is_gles2, &info);
+            // calling an entry point the driver does not provide would panic in the loader
+            let vao_oes = info.oes_vertex_array_object();
             let mut vao = 0;
-
-            glGenVertexArrays(1, &mut vao as *mut _);
-            glBindVertexArray(vao);
+            if vao_oes {
+                glGenVertexArraysOES(1, &mut vao as *mut _);
+                glBindVertexArrayOES(vao);
+            } else if features.vertex_array_object {
+                glGenVertexArrays(1, &mut vao as *mut _);
+                glBindVertexArray(vao);
+            }
             GraphicsContext {
                 default_framebuffer,
-                shaders: vec![],
-                pipelines: vec![],
-                passes: vec![],
-                features: Features::from_gles2(is_gles2),
+                vao,
+                vao_oes,
+                gles: info.gles,
+                shaders: Slab::new(),
+                pipelines: Slab::new(),
+                passes: Slab::new(),
+                pass_stack: vec![],
+                blit_quad: None,
+                composite_quad: None,
+                last_draw: Cell::new(DrawStatus::Drawn),
+                bound_index_elements: None,
+                persistent_scissor: None,
+                features,
                 cache: GlCache {
+                    framebuffer: default_framebuffer,
                     stored_index_buffer: 0,
                     stored_index_type: None,
                     stored_vertex_buffer: 0,
@@ -69,13 +169,32 @@ impl GraphicsContext {
                     index_type: None,
                     vertex_buffer: 0,
                     cur_pipeline: None,
+                    program: 0,
+                    front_face: FrontFaceOrder::CounterClockwise,
                     color_blend: None,
                     alpha_blend: None,
+                    blend_color: Color::TRANSPARENT,
                     stencil: None,
                     color_write: (true, true, true, true),
                     cull_face: CullFace::Nothing,
+                    depth_test: false,
+                    depth_func: Comparison::Less,
+                    depth_write: true,
+                    sample_coverage: None,
+                    depth_write_offset: None,
+                    viewport: None,
+                    scissor: None,
+                    scissor_test: false,
+                    depth_clamp: false,
+                    dither: true,
+                    line_width: 1.,
+                    polygon_mode: PolygonMode::Fill,
+                    program_point_size: false,
+                    color_output: true,
                     stored_texture: 0,
+                    stored_texture_3d: 0,
                     textures: [0; MAX_SHADERSTAGE_IMAGES],
+                    textures_3d: [0; MAX_SHADERSTAGE_IMAGES],
                     attributes: [None; MAX_VERTEX_ATTRIBUTES],
                 },
                 window: None,
@@ -86,6 +205,47 @@ impl GraphicsContext {
     pub fn features(&self) -> &Features {
         &self.features
     }
+
+    /// Compressed texture formats accepted by `glCompressedTexImage2D` on this GPU.
+    pub fn compressed_formats(&self) -> Vec<GLenum> {
+        let mut count: GLint = 0;
+        unsafe { glGetIntegerv(GL_NUM_COMPRESSED_TEXTURE_FORMATS, &mut count) };
+        if count <= 0 {
+            return vec![];
+        }
+
+        let mut formats = vec![0; count as usize];
+        unsafe { glGetIntegerv(GL_COMPRESSED_TEXTURE_FORMATS, formats.as_mut_ptr()) };
+        formats.into_iter().map(|format| format as GLenum).collect()
+    }
+
+    /// Drain the GL error flags, e.g. after a suspicious draw call in debug builds.
+    /// Fails with the names of all raised errors, comma separated.
+    pub fn check_errors(&self) -> Result<(), String> {
+        let mut errors = vec![];
+        // without a current context some drivers report an error on every call
+        for _ in 0..32 {
+            let error = unsafe { glGetError() };
+            let name = match error {
+                GL_NO_ERROR => break,
+                GL_INVALID_ENUM => "GL_INVALID_ENUM".to_string(),
+                GL_INVALID_VALUE => "GL_INVALID_VALUE".to_string(),
+                GL_INVALID_OPERATION => "GL_INVALID_OPERATION".to_string(),
+                GL_STACK_OVERFLOW => "GL_STACK_OVERFLOW".to_string(),
+                GL_STACK_UNDERFLOW => "GL_STACK_UNDERFLOW".to_string(),
+                GL_OUT_OF_MEMORY => "GL_OUT_OF_MEMORY".to_string(),
+                GL_INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION".to_string(),
+                error => format!("unknown GL error 0x{:04X}", error),
+            };
+            errors.push(name);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join(", "))
+        }
+    }
 }
 
 impl GraphicsContext {
@@ -121,6 +281,209 @@ impl GraphicsContext {
         self
     }
 
+    /// Switch color output of the current framebuffer off (draw buffer `GL_NONE`), e.g. for a
+    /// depth prepass, or back on (the back buffer for the default framebuffer, all color
+    /// attachments otherwise).
+    /// Should be applied after begin_pass, `begin_pass` and `end_render_pass` switch it back on
+    /// before leaving the framebuffer, so every framebuffer not bound keeps its color output.
+    pub fn set_color_output(&mut self, enabled: bool) -> &mut Self {
+        if self.cache.color_output == enabled {
+            return self;
+        }
+        unsafe {
+            let draw_buffers = if !enabled {
+                vec![GL_NONE]
+            } else {
+                let binded_fbo = self.cache.framebuffer;
+                if binded_fbo == self.default_framebuffer {
+                    // GL_BACK is only a valid draw buffer of the default framebuffer on GLES
+                    vec![if self.gles { GL_BACK } else { GL_BACK_LEFT }]
+                } else {
+                    // every attachment of an MRT pass
+                    match self.passes.iter().find(|pass| pass.gl_fb == binded_fbo) {
+                        Some(pass) if pass.draw_buffers.is_empty() => vec![GL_NONE],
+                        Some(pass) => pass.draw_buffers.clone(),
+                        None => vec![GL_COLOR_ATTACHMENT0],
+                    }
+                }
+            };
+            glDrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr());
+        }
+        self.cache.color_output = enabled;
+        self
+    }
+
+    /// Enable `GL_SAMPLE_COVERAGE` with the given `(value, invert)`, or disable it with `None`.
+    pub fn set_sample_coverage(&mut self, sample_coverage: Option<(f32, bool)>) -> &mut Self {
+        if self.cache.sample_coverage == sample_coverage {
+            return self;
+        }
+        unsafe {
+            if let Some((value, invert)) = sample_coverage {
+                if self.cache.sample_coverage.is_none() {
+                    glEnable(GL_SAMPLE_COVERAGE);
+                }
+                glSampleCoverage(value, invert as _);
+            } else {
+                glDisable(GL_SAMPLE_COVERAGE);
+            }
+        }
+        self.cache.sample_coverage = sample_coverage;
+        self
+    }
+
+    /// Enable `GL_POLYGON_OFFSET_FILL` with the given `(factor, units)`, or disable it with `None`.
+    pub fn set_depth_write_offset(&mut self, offset: Option<(f32, f32)>) -> &mut Self {
+        if self.cache.depth_write_offset == offset {
+            return self;
+        }
+        unsafe {
+            if let Some((factor, units)) = offset {
+                if self.cache.depth_write_offset.is_none() {
+                    glEnable(GL_POLYGON_OFFSET_FILL);
+                }
+                glPolygonOffset(factor, units);
+            } else {
+                glDisable(GL_POLYGON_OFFSET_FILL);
+            }
+        }
+        self.cache.depth_write_offset = offset;
+        self
+    }
+
+    /// Toggle `GL_DEPTH_TEST` without applying another pipeline.
+    /// Should be applied after apply_pipeline, which restores the pipeline's depth state.
+    pub fn set_depth_test(&mut self, enabled: bool) -> &mut Self {
+        if self.cache.depth_test == enabled {
+            return self;
+        }
+        unsafe {
+            if enabled {
+                glEnable(GL_DEPTH_TEST);
+            } else {
+                glDisable(GL_DEPTH_TEST);
+            }
+        }
+        self.cache.depth_test = enabled;
+        self
+    }
+
+    /// Change the depth compare function per draw, e.g. to flip it for reverse painter passes.
+    /// Should be applied after apply_pipeline, which restores the pipeline's `depth_test`.
+    pub fn set_depth_func(&mut self, func: Comparison) -> &mut Self {
+        if self.cache.depth_func == func {
+            return self;
+        }
+        unsafe { glDepthFunc(func.into()) };
+        self.cache.depth_func = func;
+        self
+    }
+
+    /// Toggle depth buffer writes (`glDepthMask`) without applying another pipeline.
+    /// Should be applied after apply_pipeline, which restores the pipeline's `depth_write`.
+    pub fn set_depth_write(&mut self, enabled: bool) -> &mut Self {
+        if self.cache.depth_write == enabled {
+            return self;
+        }
+        unsafe { glDepthMask(enabled as _) };
+        self.cache.depth_write = enabled;
+        self
+    }
+
+    /// Toggle `GL_DEPTH_CLAMP`, disabling near/far plane clipping.
+    ///
+    /// NOTE: no-op when `features.depth_clamp` is false (GLES, GL < 3.2).
+    pub fn set_depth_clamp(&mut self, depth_clamp: bool) -> &mut Self {
+        if !self.features.depth_clamp || self.cache.depth_clamp == depth_clamp {
+            return self;
+        }
+        unsafe {
+            if depth_clamp {
+                glEnable(GL_DEPTH_CLAMP);
+            } else {
+                glDisable(GL_DEPTH_CLAMP);
+            }
+        }
+        self.cache.depth_clamp = depth_clamp;
+        self
+    }
+
+    /// Toggle `GL_DITHER`, on by default. Disable it for reproducible output, e.g. golden-image tests.
+    pub fn set_dither(&mut self, dither: bool) -> &mut Self {
+        if self.cache.dither == dither {
+            return self;
+        }
+        unsafe {
+            if dither {
+                glEnable(GL_DITHER);
+            } else {
+                glDisable(GL_DITHER);
+            }
+        }
+        self.cache.dither = dither;
+        self
+    }
+
+    /// Width in pixels of `PrimitiveType::Lines`, 1 by default.
+    ///
+    /// NOTE: widths other than 1 are ignored when `features.wide_lines` is false.
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        if self.cache.line_width == width {
+            return self;
+        }
+        if width != 1. && !self.features.wide_lines {
+            log::warn!("Wide lines are not supported by the GPU, ignoring set_line_width");
+            return self;
+        }
+        unsafe { glLineWidth(width) };
+        self.cache.line_width = width;
+        self
+    }
+
+    /// Rasterize polygons filled, as wireframe or as points, e.g. for debug views.
+    ///
+    /// NOTE: ignored when `features.polygon_mode` is false.
+    pub fn set_polygon_mode(&mut self, mode: PolygonMode) -> &mut Self {
+        if self.cache.polygon_mode == mode {
+            return self;
+        }
+        if !self.features.polygon_mode {
+            log::warn!("Polygon modes are not supported by the GPU, ignoring set_polygon_mode");
+            return self;
+        }
+        unsafe { glPolygonMode(GL_FRONT_AND_BACK, mode.into()) };
+        self.cache.polygon_mode = mode;
+        self
+    }
+
+    /// Let vertex shaders set the size of `PrimitiveType::Points` with `gl_PointSize`.
+    ///
+    /// NOTE: always enabled on GLES, where `features.program_point_size` is false.
+    pub fn set_program_point_size(&mut self, enabled: bool) -> &mut Self {
+        if !self.features.program_point_size {
+            if !enabled {
+                log::warn!(
+                    "gl_PointSize can't be disabled on GLES, ignoring set_program_point_size"
+                );
+            }
+            return self;
+        }
+        if self.cache.program_point_size == enabled {
+            return self;
+        }
+        unsafe {
+            if enabled {
+                glEnable(GL_PROGRAM_POINT_SIZE);
+            } else {
+                glDisable(GL_PROGRAM_POINT_SIZE);
+            }
+        }
+        self.cache.program_point_size = enabled;
+        self
+    }
+
+    /// NOTE: blending is disabled with a warning when a state uses `BlendValue::Source1Color`
+    /// or `Source1Alpha` and `features.dual_source_blend` is false.
     pub fn set_blend(
         &mut self,
         color_blend: Option<BlendState>,
@@ -129,6 +492,14 @@ impl GraphicsContext {
         if color_blend.is_none() && alpha_blend.is_some() {
             panic!("AlphaBlend without ColorBlend");
         }
+        let uses_source1 = color_blend
+            .iter()
+            .chain(&alpha_blend)
+            .any(|b| b.uses_source1());
+        if uses_source1 && !self.features.dual_source_blend {
+            log::warn!("Dual-source blending is not supported, blending is ignored");
+            return self.set_blend(None, None);
+        }
         if self.cache.color_blend == color_blend && self.cache.alpha_blend == alpha_blend {
             return self;
         }
@@ -172,6 +543,18 @@ impl GraphicsContext {
         self
     }
 
+    /// Set the color used by `BlendValue::ConstantColor` and `BlendValue::ConstantAlpha`,
+    /// transparent black by default.
+    pub fn set_blend_color(&mut self, r: f32, g: f32, b: f32, a: f32) -> &mut Self {
+        let color = Color::new(r, g, b, a);
+        if self.cache.blend_color == color {
+            return self;
+        }
+        unsafe { glBlendColor(r, g, b, a) }
+        self.cache.blend_color = color;
+        self
+    }
+
     pub fn set_stencil(&mut self, stencil_test: Option<StencilState>) -> &mut Self {
         if self.cache.stencil == stencil_test {
             return self;
@@ -220,21 +603,160 @@ impl GraphicsContext {
         self
     }
 
+    /// Change only the stencil reference value of `face`, keeping compare functions and masks
+    /// of the current stencil state, e.g. to test many draws against a value written earlier.
+    /// The other face keeps its reference, for two-sided stencil techniques.
+    /// Should be applied after apply_pipeline, which restores the pipeline's reference.
+    ///
+    /// NOTE: no-op while the stencil test is disabled.
+    pub fn set_stencil_ref(&mut self, face: StencilFace, test_ref: i32) -> &mut Self {
+        let mut stencil = match self.cache.stencil {
+            Some(stencil) => stencil,
+            None => return self,
+        };
+
+        unsafe {
+            let front = &mut stencil.front;
+            if face != StencilFace::Back && front.test_ref != test_ref {
+                front.test_ref = test_ref;
+                glStencilFuncSeparate(GL_FRONT, front.test_func.into(), test_ref, front.test_mask);
+            }
+            let back = &mut stencil.back;
+            if face != StencilFace::Front && back.test_ref != test_ref {
+                back.test_ref = test_ref;
+                glStencilFuncSeparate(GL_BACK, back.test_func.into(), test_ref, back.test_mask);
+            }
+        }
+
+        self.cache.stencil = Some(stencil);
+        self
+    }
+
     /// Set a new viewport rectangle.
     /// Should be applied after begin_pass.
     pub fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32) -> &mut Self {
+        if self.cache.viewport == Some((x, y, w, h)) {
+            return self;
+        }
         unsafe {
             glViewport(x, y, w, h);
         }
+        self.cache.viewport = Some((x, y, w, h));
+        self
+    }
+
+    /// Set viewports `0..viewports.len()` at once, to be selected with
+    /// `gl_ViewportIndex` in a geometry shader.
+    /// Should be applied after begin_pass.
+    ///
+    /// NOTE: requires GL 4.1 or `ARB_viewport_array`.
+    /// `features.viewport_array` check is required.
+    pub fn set_viewports(&mut self, viewports: &[Viewport]) -> &mut Self {
+        if !self.features.viewport_array {
+            log::warn!("Viewport arrays are not supported by the GPU, ignoring set_viewports");
+            return self;
+        }
+
+        let data: Vec<f32> = viewports
+            .iter()
+            .flat_map(|v| [v.x as f32, v.y as f32, v.w as f32, v.h as f32])
+            .collect();
+        unsafe {
+            glViewportArrayv(0, viewports.len() as _, data.as_ptr());
+        }
+        // viewports beyond the first are not tracked, the next apply_viewport has to reset them all
+        self.cache.viewport = None;
         self
     }
 
     /// Set a new scissor rectangle.
     /// Should be applied after begin_pass.
     pub fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32) -> &mut Self {
+        if self.cache.scissor == Some((x, y, w, h)) {
+            return self;
+        }
         unsafe {
             glScissor(x, y, w, h);
         }
+        self.cache.scissor = Some((x, y, w, h));
+        self
+    }
+
+    /// Scissor rectangle applied again after `begin_pass` and `end_render_pass` reset the scissor
+    /// to the full framebuffer, e.g. for a static UI clip region across passes.
+    /// Pass clears are not clipped. `None` restores the default behavior from the next pass on.
+    pub fn set_persistent_scissor(&mut self, scissor: Option<Viewport>) -> &mut Self {
+        self.persistent_scissor = scissor;
+        self.apply_persistent_scissor();
+        self
+    }
+
+    fn apply_persistent_scissor(&mut self) {
+        if let Some(Viewport { x, y, w, h }) = self.persistent_scissor {
+            self.apply_scissor_rect(x, y, w, h);
+        }
+    }
+
+    /// Clear only the `x, y, w, h` rectangle of the current framebuffer.
+    /// Previous scissor rectangle and scissor test state are restored afterwards.
+    pub fn clear_rect(&mut self, x: i32, y: i32, w: i32, h: i32, clear: Clear) -> &mut Self {
+        let scissor = self.cache.scissor;
+        let scissor_test = self.cache.scissor_test;
+
+        self.cache.set_scissor_test(true);
+        self.apply_scissor_rect(x, y, w, h);
+        self.clear(clear);
+
+        match scissor {
+            Some((sx, sy, sw, sh)) => {
+                self.apply_scissor_rect(sx, sy, sw, sh);
+            }
+            // no scissor was set yet, fall back to the one of the current pass
+            None => {
+                if let Some(&(_, pw, ph)) = self.pass_stack.last() {
+                    self.apply_scissor_rect(0, 0, pw, ph);
+                }
+                self.apply_persistent_scissor();
+            }
+        }
+        self.cache.set_scissor_test(scissor_test);
+        self
+    }
+
+    /// Reset the stencil buffer to `value` inside the `x, y, w, h` rectangle only,
+    /// e.g. between UI windows. Writes all stencil bits regardless of the current write mask,
+    /// the write masks and scissor state are restored afterwards.
+    pub fn clear_stencil_rect(&mut self, x: i32, y: i32, w: i32, h: i32, value: i32) -> &mut Self {
+        let mut front_mask = 0;
+        let mut back_mask = 0;
+        unsafe {
+            glGetIntegerv(GL_STENCIL_WRITEMASK, &mut front_mask);
+            glGetIntegerv(GL_STENCIL_BACK_WRITEMASK, &mut back_mask);
+            glStencilMask(!0);
+        }
+
+        self.clear_rect(x, y, w, h, Clear::new().stencil(value));
+
+        unsafe {
+            glStencilMaskSeparate(GL_FRONT, front_mask as GLuint);
+            glStencilMaskSeparate(GL_BACK, back_mask as GLuint);
+        }
+        self
+    }
+
+    /// Clear only the color attachment `index` of the current pass to `color`, e.g. to
+    /// initialize each texture of a G-buffer to a different value.
+    /// Must be called after `begin_pass` bound the pass framebuffer,
+    /// channels disabled with `set_color_write` are left untouched.
+    ///
+    /// NOTE: `features.clear_buffer` check is required, GL 3.0 or GLES 3.0.
+    pub fn clear_color_attachment(&mut self, index: i32, color: [f32; 4]) -> &mut Self {
+        assert!(
+            self.features.clear_buffer,
+            "glClearBufferfv requires GL 3.0 or GLES 3.0"
+        );
+        assert!(index >= 0, "Negative color attachment index {}", index);
+        unsafe { glClearBufferfv(GL_COLOR, index, color.as_ptr()) };
         self
     }
 
@@ -242,6 +764,15 @@ impl GraphicsContext {
         let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
         let shader = &self.shaders[pip.shader.0];
 
+        if let Some(max_buffer_index) = pip.layout.iter().flatten().map(|a| a.buffer_index).max() {
+            assert!(
+                max_buffer_index < bindings.vertex_buffers.len(),
+                "Pipeline expects {} vertex buffers, bindings provided {}",
+                max_buffer_index + 1,
+                bindings.vertex_buffers.len()
+            );
+        }
+
         for (n, shader_image) in shader.images.iter().enumerate() {
             let bindings_image = bindings
                 .images
@@ -255,11 +786,29 @@ impl GraphicsContext {
             }
         }
 
+        debug_assert!(
+            bindings.index_buffer.buffer_type == BufferType::IndexBuffer,
+            "Bindings.index_buffer is a {:?}, not an IndexBuffer",
+            bindings.index_buffer.buffer_type
+        );
+        for (n, vertex_buffer) in bindings.vertex_buffers.iter().enumerate() {
+            debug_assert!(
+                vertex_buffer.buffer_type == BufferType::VertexBuffer,
+                "Bindings.vertex_buffers[{}] is a {:?}, not a VertexBuffer",
+                n,
+                vertex_buffer.buffer_type
+            );
+        }
+
         self.cache.bind_buffer(
             GL_ELEMENT_ARRAY_BUFFER,
             bindings.index_buffer.gl_buf,
             bindings.index_buffer.index_type,
         );
+        self.bound_index_elements = bindings
+            .index_buffer
+            .index_type
+            .map(|index_type| bindings.index_buffer.size / index_type.size() as usize);
 
         let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
 
@@ -270,6 +819,15 @@ impl GraphicsContext {
 
             if let Some(Some(attribute)) = pip_attribute {
                 let vb = &bindings.vertex_buffers[attribute.buffer_index];
+                let buffer_offset = bindings
+                    .vertex_buffer_offsets
+                    .get(attribute.buffer_index)
+                    .copied()
+                    .unwrap_or(0);
+                let attribute = VertexAttributeInternal {
+                    offset: attribute.offset + buffer_offset,
+                    ..attribute
+                };
 
                 if cached_attr.map_or(true, |cached_attr| {
                     attribute != cached_attr.attribute || cached_attr.gl_vbuf != vb.gl_buf
@@ -282,7 +840,7 @@ impl GraphicsContext {
                             attr_index as GLuint,
                             attribute.size,
                             attribute.type_,
-                            GL_FALSE as u8,
+                            attribute.normalized as u8,
                             attribute.stride,
                             attribute.offset as *mut _,
                         );
@@ -310,72 +868,103 @@ impl GraphicsContext {
         self
     }
 
+    /// Disable every vertex attribute array and forget the cached attribute state.
+    /// Useful before handing the GL context over to other rendering code.
+    pub fn disable_all_attributes(&mut self) -> &mut Self {
+        for attr_index in 0..MAX_VERTEX_ATTRIBUTES {
+            unsafe {
+                glDisableVertexAttribArray(attr_index as GLuint);
+            }
+            self.cache.attributes[attr_index] = None;
+        }
+        self
+    }
+
+    /// Select the active texture unit, `GL_TEXTURE0 + unit`.
+    /// Intended for interop with other GL code, the crate itself sets the unit on every texture bind.
+    pub fn set_active_texture(&mut self, unit: u32) -> &mut Self {
+        unsafe {
+            glActiveTexture(GL_TEXTURE0 + unit);
+        }
+        self
+    }
+
     pub fn apply_uniforms<U>(&mut self, uniforms: &U) -> &mut Self {
-        self.apply_uniforms_from_bytes(uniforms as *const _ as *const u8, std::mem::size_of::<U>());
+        let bytes = unsafe {
+            std::slice::from_raw_parts(uniforms as *const _ as *const u8, std::mem::size_of::<U>())
+        };
+        self.apply_uniforms_from_bytes(bytes);
+        self
+    }
+
+    /// Set a single uniform of the applied pipeline's shader by its `ShaderMeta` name,
+    /// without the whole `apply_uniforms` struct.
+    /// ```ignore
+    /// ctx.set_uniform("time", 1.5f32);
+    /// ctx.set_uniform("model", glam::Mat4::IDENTITY);
+    /// ```
+    /// Panics when the name is not declared in the shader meta or the size of `T`
+    /// doesn't match the uniform type and array count.
+    pub fn set_uniform<T>(&mut self, name: &str, value: T) -> &mut Self {
+        let pipeline = self
+            .cache
+            .cur_pipeline
+            .expect("set_uniform without an applied pipeline");
+        let shader = &self.shaders[self.pipelines[pipeline.0].shader.0];
+        let uniform = shader
+            .uniforms
+            .iter()
+            .find(|uniform| uniform.name == name)
+            .unwrap_or_else(|| panic!("Uniform {:?} is not declared in the shader meta", name));
+
+        let uniform_size = uniform.uniform_type.size() * uniform.array_count as usize;
+        assert_eq!(
+            mem::size_of::<T>(),
+            uniform_size,
+            "Uniform {:?} is {:?} x{}, {} bytes",
+            name,
+            uniform.uniform_type,
+            uniform.array_count,
+            uniform_size
+        );
+
+        unsafe { uniform.apply(&value as *const T as *const u8) };
         self
     }
 
     #[doc(hidden)]
     /// Apply uniforms data from array of bytes with very special layout.
     /// Hidden because `apply_uniforms` is the recommended and safer way to work with uniforms.
-    pub fn apply_uniforms_from_bytes(&mut self, uniform_ptr: *const u8, size: usize) -> &mut Self {
+    pub fn apply_uniforms_from_bytes(&mut self, bytes: &[u8]) -> &mut Self {
         let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
         let shader = &self.shaders[pip.shader.0];
 
-        let mut offset = 0;
-
-        for (_, uniform) in shader.uniforms.iter().enumerate() {
-            use UniformType::*;
-
-            assert!(
-                offset <= size - uniform.uniform_type.size() / 4,
-                "Uniforms struct does not match shader uniforms layout"
-            );
+        // in bytes, a struct smaller than a single uniform must not underflow
+        let expected_size: usize = shader
+            .uniforms
+            .iter()
+            .map(|uniform| uniform.uniform_type.size() * uniform.array_count as usize)
+            .sum();
+        assert!(
+            expected_size <= bytes.len(),
+            "Uniforms struct does not match shader uniforms layout: expected {} bytes, got {}",
+            expected_size,
+            bytes.len()
+        );
 
-            unsafe {
-                let data = (uniform_ptr as *const f32).offset(offset as isize);
-                let data_int = (uniform_ptr as *const i32).offset(offset as isize);
+        // in bytes
+        let mut offset = 0;
 
-                if let Some(gl_loc) = uniform.gl_loc {
-                    match uniform.uniform_type {
-                        Float1 => {
-                            glUniform1fv(gl_loc, uniform.array_count, data);
-                        }
-                        Float2 => {
-                            glUniform2fv(gl_loc, uniform.array_count, data);
-                        }
-                        Float3 => {
-                            glUniform3fv(gl_loc, uniform.array_count, data);
-                        }
-                        Float4 => {
-                            glUniform4fv(gl_loc, uniform.array_count, data);
-                        }
-                        Int1 => {
-                            glUniform1iv(gl_loc, uniform.array_count, data_int);
-                        }
-                        Int2 => {
-                            glUniform2iv(gl_loc, uniform.array_count, data_int);
-                        }
-                        Int3 => {
-                            glUniform3iv(gl_loc, uniform.array_count, data_int);
-                        }
-                        Int4 => {
-                            glUniform4iv(gl_loc, uniform.array_count, data_int);
-                        }
-                        Mat4 => {
-                            glUniformMatrix4fv(gl_loc, uniform.array_count, 0, data);
-                        }
-                    }
-                }
-            }
-            offset += uniform.uniform_type.size() / 4 * uniform.array_count as usize;
+        for uniform in shader.uniforms.iter() {
+            unsafe { uniform.apply(bytes[offset..].as_ptr()) };
+            offset += uniform.uniform_type.size() * uniform.array_count as usize;
         }
         self
     }
 
     #[inline]
     pub fn clear(&self, clear: Clear) {
-        clear.apply()
+        clear.apply(self.cache.depth_write)
     }
 
     /// Draw elements using currently applied bindings and pipeline.
@@ -385,23 +974,100 @@ impl GraphicsContext {
     /// + `num_instances` specifies how many instances should be rendered.
     ///
     /// NOTE: num_instances > 1 might be not supported by the GPU (gl2.1 and gles2).
-    /// `features.instancing` check is required.
+    /// Without `features.instancing` the geometry is drawn `num_instances` times in a loop,
+    /// setting the `uniform int instance_id;` of the shader (if declared) before each draw.
+    /// Per-instance vertex attributes do not advance in this fallback.
     pub fn draw(&self, base_element: i32, num_elements: i32, num_instances: i32) -> &Self {
+        self.draw_elements(base_element, num_elements, num_instances);
+        self.draw_wireframe_overlay(|| {
+            self.draw_elements(base_element, num_elements, num_instances);
+        });
+        self
+    }
+
+    /// Like `draw`, but checks `base_element..base_element + num_elements` against
+    /// the index buffer of the applied `Bindings` instead of reading past its end.
+    pub fn try_draw(
+        &self,
+        base_element: i32,
+        num_elements: i32,
+        num_instances: i32,
+    ) -> Result<(), DrawError> {
+        check_draw_range(base_element, num_elements, self.bound_index_elements)?;
+        self.draw(base_element, num_elements, num_instances);
+        Ok(())
+    }
+
+    /// Like `draw` with a single instance, telling the driver that the drawn indices
+    /// all lie within `start..=end` with `glDrawRangeElements`.
+    ///
+    /// NOTE: the range is only a hint, a plain `glDrawElements` is used
+    /// when `features.draw_range_elements` is false (GLES2).
+    pub fn draw_range(&self, start: u32, end: u32, base_element: i32, num_elements: i32) -> &Self {
+        assert!(
+            start <= end,
+            "draw_range start {} is past end {}",
+            start,
+            end
+        );
+        if !self.features.draw_range_elements {
+            return self.draw(base_element, num_elements, 1);
+        }
+
+        let draw = || {
+            assert!(
+                self.cache.cur_pipeline.is_some(),
+                "Drawing without any binded pipeline"
+            );
+            let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
+            let index_type = self.cache.index_type.expect("Unset index buffer type");
+            unsafe {
+                glDrawRangeElements(
+                    pip.params.primitive_type.into(),
+                    start,
+                    end,
+                    num_elements,
+                    index_type.into(),
+                    (index_type.size() as i32 * base_element) as *mut _,
+                );
+            }
+        };
+        draw();
+        self.draw_wireframe_overlay(draw);
+        self.last_draw.set(DrawStatus::Drawn);
+        self
+    }
+
+    fn draw_elements(&self, base_element: i32, num_elements: i32, num_instances: i32) {
         assert!(
             self.cache.cur_pipeline.is_some(),
             "Drawing without any binded pipeline"
         );
 
-        if !self.features.instancing && num_instances != 1 {
-            eprintln!("Instanced rendering is not supported by the GPU");
-            eprintln!("Ignoring this draw call");
-            return self;
-        }
-
         let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
         let primitive_type = pip.params.primitive_type.into();
         let index_type = self.cache.index_type.expect("Unset index buffer type");
 
+        if !self.features.instancing && num_instances != 1 {
+            let instance_id = self.shaders[pip.shader.0].instance_id;
+            for instance in 0..num_instances {
+                unsafe {
+                    if let Some(gl_loc) = instance_id {
+                        glUniform1i(gl_loc, instance);
+                    }
+                    glDrawElements(
+                        primitive_type,
+                        num_elements,
+                        index_type.into(),
+                        (index_type.size() as i32 * base_element) as *mut _,
+                    );
+                }
+            }
+            self.last_draw.set(DrawStatus::InstancingEmulated);
+            return;
+        }
+        self.last_draw.set(DrawStatus::Drawn);
+
         unsafe {
             if self.features.instancing {
                 glDrawElementsInstanced(
@@ -420,8 +1086,212 @@ impl GraphicsContext {
                 );
             }
         }
+    }
+
+    /// How the last `draw*` call was handled, e.g. to tell users that instancing
+    /// is unavailable instead of showing a blank screen.
+    pub fn last_draw_status(&self) -> DrawStatus {
+        self.last_draw.get()
+    }
+
+    /// The last `draw*` call was dropped for missing GPU support
+    pub fn last_draw_skipped(&self) -> bool {
+        self.last_draw.get() == DrawStatus::Skipped
+    }
+
+    /// Draw `count` vertices starting at vertex `first` without an index buffer,
+    /// e.g. for fullscreen triangles, point clouds or debug lines.
+    pub fn draw_arrays(&self, first: i32, count: i32, num_instances: i32) -> &Self {
+        self.draw_arrays_internal(first, count, num_instances);
+        self.draw_wireframe_overlay(|| self.draw_arrays_internal(first, count, num_instances));
         self
     }
+
+    fn draw_arrays_internal(&self, first: i32, count: i32, num_instances: i32) {
+        assert!(
+            self.cache.cur_pipeline.is_some(),
+            "Drawing without any binded pipeline"
+        );
+
+        let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
+        let primitive_type = pip.params.primitive_type.into();
+
+        if !self.features.instancing && num_instances != 1 {
+            let instance_id = self.shaders[pip.shader.0].instance_id;
+            for instance in 0..num_instances {
+                unsafe {
+                    if let Some(gl_loc) = instance_id {
+                        glUniform1i(gl_loc, instance);
+                    }
+                    glDrawArrays(primitive_type, first, count);
+                }
+            }
+            self.last_draw.set(DrawStatus::InstancingEmulated);
+            return;
+        }
+        self.last_draw.set(DrawStatus::Drawn);
+
+        unsafe {
+            if self.features.instancing {
+                glDrawArraysInstanced(primitive_type, first, count, num_instances);
+            } else {
+                glDrawArrays(primitive_type, first, count);
+            }
+        }
+    }
+
+    /// Run `draw` again in line polygon mode when the applied pipeline has a `wireframe_overlay`
+    fn draw_wireframe_overlay(&self, draw: impl Fn()) {
+        let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
+        let Color { r, g, b, a } = match pip.params.wireframe_overlay {
+            Some(color) => color,
+            None => return,
+        };
+        if !self.features.polygon_mode {
+            log::warn!("Polygon modes are not supported by the GPU, ignoring wireframe_overlay");
+            return;
+        }
+        let wireframe_color = self.shaders[pip.shader.0].wireframe_color;
+
+        unsafe {
+            if let Some(gl_loc) = wireframe_color {
+                glUniform4f(gl_loc, r, g, b, a);
+            }
+            glPolygonMode(GL_FRONT_AND_BACK, GL_LINE);
+            // pull the edges in front of the filled triangles they were drawn from
+            glEnable(GL_POLYGON_OFFSET_LINE);
+            glPolygonOffset(-1., -1.);
+        }
+        draw();
+        unsafe {
+            if let Some(gl_loc) = wireframe_color {
+                glUniform4f(gl_loc, 0., 0., 0., 0.);
+            }
+            glPolygonMode(GL_FRONT_AND_BACK, self.cache.polygon_mode.into());
+            glDisable(GL_POLYGON_OFFSET_LINE);
+            let (factor, units) = self.cache.depth_write_offset.unwrap_or((0., 0.));
+            glPolygonOffset(factor, units);
+        }
+    }
+
+    /// Draw elements like `draw`, but per-instance attributes are fetched
+    /// starting from `base_instance` instead of 0.
+    ///
+    /// NOTE: requires GL 4.2 or `ARB_base_instance`.
+    /// `features.base_instance` check is required.
+    pub fn draw_instanced_base_instance(
+        &self,
+        base_element: i32,
+        num_elements: i32,
+        num_instances: i32,
+        base_instance: u32,
+    ) -> &Self {
+        assert!(
+            self.cache.cur_pipeline.is_some(),
+            "Drawing without any binded pipeline"
+        );
+
+        if !self.features.base_instance {
+            log::warn!(
+                "Base instance drawing is not supported by the GPU, ignoring this draw call"
+            );
+            self.last_draw.set(DrawStatus::Skipped);
+            return self;
+        }
+        self.last_draw.set(DrawStatus::Drawn);
+
+        let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
+        let primitive_type = pip.params.primitive_type.into();
+        let index_type = self.cache.index_type.expect("Unset index buffer type");
+
+        unsafe {
+            glDrawElementsInstancedBaseInstance(
+                primitive_type,
+                num_elements,
+                index_type.into(),
+                (index_type.size() as i32 * base_element) as *mut _,
+                num_instances,
+                base_instance,
+            );
+        }
+        self
+    }
+
+    /// Bind the `BufferType::DrawIndirect` buffer read by `draw_indirect` and `multi_draw_indirect`.
+    pub fn apply_indirect_buffer(&mut self, buffer: &Buffer) -> &mut Self {
+        assert!(
+            buffer.buffer_type == BufferType::DrawIndirect,
+            "Indirect draws read from BufferType::DrawIndirect buffers"
+        );
+        self.cache
+            .bind_buffer(GL_DRAW_INDIRECT_BUFFER, buffer.gl_buf, None);
+        self
+    }
+
+    /// Draw elements with the `DrawIndirectCommand` at byte `offset` of the applied indirect buffer,
+    /// e.g. written by a compute shader.
+    ///
+    /// NOTE: `features.draw_indirect` check is required.
+    pub fn draw_indirect(&self, offset: usize) -> &Self {
+        self.multi_draw_indirect_internal(offset, None)
+    }
+
+    /// Draw `draw_count` `DrawIndirectCommand`s, `stride` bytes apart (0 for tightly packed),
+    /// starting at byte `offset` of the applied indirect buffer.
+    ///
+    /// NOTE: `features.multi_draw_indirect` check is required.
+    pub fn multi_draw_indirect(&self, offset: usize, draw_count: i32, stride: i32) -> &Self {
+        self.multi_draw_indirect_internal(offset, Some((draw_count, stride)))
+    }
+
+    fn multi_draw_indirect_internal(&self, offset: usize, multi: Option<(i32, i32)>) -> &Self {
+        assert!(
+            self.cache.cur_pipeline.is_some(),
+            "Drawing without any binded pipeline"
+        );
+
+        let supported = match multi {
+            None => self.features.draw_indirect,
+            Some(_) => self.features.multi_draw_indirect,
+        };
+        if !supported {
+            log::warn!("Indirect drawing is not supported by the GPU, ignoring this draw call");
+            self.last_draw.set(DrawStatus::Skipped);
+            return self;
+        }
+        self.last_draw.set(DrawStatus::Drawn);
+
+        let pip = &self.pipelines[self.cache.cur_pipeline.unwrap().0];
+        let primitive_type = pip.params.primitive_type.into();
+        let index_type = self.cache.index_type.expect("Unset index buffer type");
+
+        unsafe {
+            match multi {
+                None => {
+                    glDrawElementsIndirect(primitive_type, index_type.into(), offset as *const _)
+                }
+                Some((draw_count, stride)) => glMultiDrawElementsIndirect(
+                    primitive_type,
+                    index_type.into(),
+                    offset as *const _,
+                    draw_count,
+                    stride,
+                ),
+            }
+        }
+        self
+    }
+}
+
+/// Layout of a single indirect draw command, as read by `draw_indirect`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrawIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub base_instance: u32,
 }
 
 impl GraphicsContext {
@@ -436,13 +1306,36 @@ impl GraphicsContext {
 
 impl Drop for GraphicsContext {
     fn drop(&mut self) {
-        todo!()
+        // the queue is per thread, pending names must not leak into a later context
+        let pending = take_pending_deletions();
+
+        // objects were already freed together with the GL context, e.g. when the window was destroyed
+        if unsafe { glfw::ffi::glfwGetCurrentContext() }.is_null() {
+            return;
+        }
+
+        unsafe {
+            for object in pending {
+                object.delete();
+            }
+            for shader in self.shaders.iter() {
+                glDeleteProgram(shader.program);
+            }
+            for pass in self.passes.iter() {
+                pass.delete_gl_objects();
+            }
+            if self.vao_oes {
+                glDeleteVertexArraysOES(1, &self.vao);
+            } else if self.vao != 0 {
+                glDeleteVertexArrays(1, &self.vao);
+            }
+        }
     }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Clear {
-    color: Option<(f32, f32, f32, f32)>,
+    color: Option<Color>,
     depth: Option<f32>,
     stencil: Option<i32>,
 }
@@ -455,7 +1348,13 @@ impl Clear {
 
     #[inline]
     pub fn color(mut self, r: f32, g: f32, b: f32, a: f32) -> Self {
-        self.color = Some((r, g, b, a));
+        self.color = Some(Color::new(r, g, b, a));
+        self
+    }
+
+    #[inline]
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
         self
     }
 
@@ -471,15 +1370,16 @@ impl Clear {
         self
     }
 
+    /// `depth_write` is the cached `glDepthMask`, depth clears force it on meanwhile
     #[inline]
-    pub fn apply(self) {
+    pub(crate) fn apply(self, depth_write: bool) {
         let Self {
             color,
             depth,
             stencil,
         } = self;
         let mut bits = 0;
-        if let Some((r, g, b, a)) = color {
+        if let Some(Color { r, g, b, a }) = color {
             bits |= GL_COLOR_BUFFER_BIT;
             unsafe {
                 glClearColor(r, g, b, a);
@@ -502,8 +1402,63 @@ impl Clear {
 
         if bits != 0 {
             unsafe {
+                // depth clears are masked by glDepthMask, a pipeline without depth_write disables it
+                let force_depth_write = depth.is_some() && !depth_write;
+                if force_depth_write {
+                    glDepthMask(1);
+                }
                 glClear(bits);
+                if force_depth_write {
+                    glDepthMask(0);
+                }
             }
         }
     }
 }
+
+/// `base..base + count` has to lie within the `elements` of the bound index buffer
+fn check_draw_range(base: i32, count: i32, elements: Option<usize>) -> Result<(), DrawError> {
+    let elements = elements.ok_or(DrawError::NoIndexBuffer)?;
+    let end = base as i64 + count as i64;
+    if base < 0 || count < 0 || end > elements as i64 {
+        return Err(DrawError::OutOfRange {
+            base,
+            count,
+            elements,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_range_within_index_buffer() {
+        assert_eq!(check_draw_range(0, 6, Some(6)), Ok(()));
+        assert_eq!(check_draw_range(3, 3, Some(6)), Ok(()));
+        assert_eq!(check_draw_range(6, 0, Some(6)), Ok(()));
+    }
+
+    #[test]
+    fn draw_range_out_of_index_buffer() {
+        let out_of_range = |base, count| DrawError::OutOfRange {
+            base,
+            count,
+            elements: 6,
+        };
+        assert_eq!(check_draw_range(1, 6, Some(6)), Err(out_of_range(1, 6)));
+        assert_eq!(check_draw_range(-1, 2, Some(6)), Err(out_of_range(-1, 2)));
+        assert_eq!(check_draw_range(0, -1, Some(6)), Err(out_of_range(0, -1)));
+        assert_eq!(
+            check_draw_range(i32::MAX, i32::MAX, Some(6)),
+            Err(out_of_range(i32::MAX, i32::MAX))
+        );
+    }
+
+    #[test]
+    fn draw_range_without_bindings() {
+        assert_eq!(check_draw_range(0, 0, None), Err(DrawError::NoIndexBuffer));
+    }
+}
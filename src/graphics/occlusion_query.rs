@@ -0,0 +1,125 @@
+use super::*;
+/// `OcclusionQuery` counts the samples that passed the depth and stencil tests
+/// between [`OcclusionQuery::begin_query()`] and [`OcclusionQuery::end_query()`].
+///
+/// Besides reading the result back with [`OcclusionQuery::get_result()`], the query can drive
+/// [`GraphicsContext::begin_conditional_render()`] so the GPU skips draws of occluded objects
+/// without a CPU round trip.
+///
+/// Implemented with `GL_SAMPLES_PASSED` queries, not available on GLES.
+/// Use [`OcclusionQuery::is_supported()`] to check if functionality is available.
+pub struct OcclusionQuery {
+    pub(crate) gl_query: GLuint,
+}
+
+impl Default for OcclusionQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OcclusionQuery {
+    pub fn new() -> OcclusionQuery {
+        OcclusionQuery { gl_query: 0 }
+    }
+
+    /// Reports whenever occlusion queries are supported and other methods can be invoked.
+    pub fn is_supported(ctx: &GraphicsContext) -> bool {
+        ctx.features.occlusion_query
+    }
+
+    /// Submit a beginning of occlusion query.
+    ///
+    /// Only a single occlusion query can be active at any moment in time.
+    ///
+    /// Panics when `features.occlusion_query` is false.
+    pub fn begin_query(&mut self, ctx: &GraphicsContext) {
+        assert!(
+            ctx.features.occlusion_query,
+            "Occlusion queries are not supported by the GPU"
+        );
+        if self.gl_query == 0 {
+            unsafe { glGenQueries(1, &mut self.gl_query) };
+        }
+        unsafe { glBeginQuery(GL_SAMPLES_PASSED, self.gl_query) };
+    }
+
+    /// Submit an end of occlusion query that can be read later when rendering is complete.
+    pub fn end_query(&mut self) {
+        unsafe { glEndQuery(GL_SAMPLES_PASSED) };
+    }
+
+    /// Number of samples that passed, stalls until the result is available.
+    pub fn get_result(&self) -> u32 {
+        // the query exists only after a supported begin_query
+        assert!(self.gl_query != 0, "get_result called before begin_query");
+        let mut samples: GLint = 0;
+        unsafe { glGetQueryObjectiv(self.gl_query, GL_QUERY_RESULT, &mut samples) };
+        samples as u32
+    }
+
+    /// Reports whenever result of submitted query is available for retrieval with
+    /// [`OcclusionQuery::get_result()`].
+    pub fn is_available(&self) -> bool {
+        // begin_query was not called yet, or the query is not supported
+        if self.gl_query == 0 {
+            return false;
+        }
+
+        let mut available: GLint = 0;
+        unsafe { glGetQueryObjectiv(self.gl_query, GL_QUERY_RESULT_AVAILABLE, &mut available) };
+        available != 0
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        if self.gl_query != 0 {
+            unsafe { glDeleteQueries(1, &self.gl_query) }
+        }
+    }
+}
+
+/// How the GPU waits for the query result in conditional rendering.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConditionalRenderMode {
+    /// Wait for the query result before rendering
+    Wait,
+    /// Render unconditionally if the result is not ready yet
+    NoWait,
+    /// Like `Wait`, but results may be evaluated per screen region
+    ByRegionWait,
+    /// Like `NoWait`, but results may be evaluated per screen region
+    ByRegionNoWait,
+}
+
+impl GraphicsContext {
+    /// Skip all draws until [`GraphicsContext::end_conditional_render()`] when `query`
+    /// reported zero passed samples.
+    ///
+    /// NOTE: requires GL 3.0, draws are always performed when
+    /// `features.conditional_render` is false.
+    ///
+    /// Panics when `begin_query` was never called on `query`.
+    pub fn begin_conditional_render(
+        &mut self,
+        query: &OcclusionQuery,
+        mode: ConditionalRenderMode,
+    ) -> &mut Self {
+        assert!(
+            query.gl_query != 0,
+            "begin_conditional_render called before begin_query"
+        );
+        if self.features.conditional_render {
+            unsafe { glBeginConditionalRender(query.gl_query, mode.into()) };
+        }
+        self
+    }
+
+    pub fn end_conditional_render(&mut self) -> &mut Self {
+        if self.features.conditional_render {
+            unsafe { glEndConditionalRender() };
+        }
+        self
+    }
+}
@@ -6,8 +6,12 @@ pub enum PassAction {
 
 impl PassAction {
     pub fn clear_color(r: f32, g: f32, b: f32, a: f32) -> PassAction {
+        Self::clear_with_color(Color::new(r, g, b, a))
+    }
+
+    pub fn clear_with_color(color: Color) -> PassAction {
         PassAction::Clear(Clear {
-            color: Some((r, g, b, a)),
+            color: Some(color),
             depth: Some(1.),
             stencil: None,
         })
@@ -17,28 +21,185 @@ impl PassAction {
 impl Default for PassAction {
     fn default() -> PassAction {
         PassAction::Clear(Clear {
-            color: Some((0.0, 0.0, 0.0, 0.0)),
+            color: Some(Color::TRANSPARENT),
             depth: Some(1.),
             stencil: None,
         })
     }
 }
 
+/// Storage format of a depth renderbuffer
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DepthFormat {
+    Depth16,
+    Depth24,
+    /// 24 bit depth with an 8 bit stencil buffer
+    Depth24Stencil8,
+}
+
+impl DepthFormat {
+    /// (internal_format, attachment)
+    fn gl_format(self) -> (GLenum, GLenum) {
+        match self {
+            DepthFormat::Depth16 => (GL_DEPTH_COMPONENT16, GL_DEPTH_ATTACHMENT),
+            DepthFormat::Depth24 => (GL_DEPTH_COMPONENT24, GL_DEPTH_ATTACHMENT),
+            DepthFormat::Depth24Stencil8 => (GL_DEPTH24_STENCIL8, GL_DEPTH_STENCIL_ATTACHMENT),
+        }
+    }
+}
+
+/// Invalid attachment combination given to [`RenderPassBuilder::build`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenderPassError {
+    /// Neither a color nor a depth texture was given
+    NoAttachments,
+    /// More color textures than `max_draw_buffers`/`max_color_attachments` allow
+    TooManyColorAttachments { count: usize, max: usize },
+    /// Both `depth` and `depth_renderbuffer` were given
+    ConflictingDepth,
+    /// An attachment differs in size from the first one
+    SizeMismatch {
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+    /// More samples than `GL_MAX_SAMPLES`
+    TooManySamples { samples: i32, max: i32 },
+    /// Multisampled passes need `depth_renderbuffer`, textures can't be multisampled
+    MultisampleDepthTexture,
+    /// `glCheckFramebufferStatus` result other than `GL_FRAMEBUFFER_COMPLETE`
+    Incomplete(GLenum),
+}
+
+impl Display for RenderPassError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self) // Display the same way as Debug
+    }
+}
+
+impl Error for RenderPassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
 pub(crate) struct RenderPassInternal {
     pub(crate) gl_fb: GLuint,
-    pub(crate) texture: Texture,
+    /// Depth renderbuffer of `new_with_depthbuffer` passes, 0 otherwise
+    pub(crate) gl_depth_rb: GLuint,
+    pub(crate) textures: Vec<Texture>,
     pub(crate) _depth_texture: Option<Texture>,
+    /// Volume texture of a `new_3d_slice` pass, `textures` is empty then
+    pub(crate) texture_3d: Option<Texture3D>,
+    /// Multisampled color renderbuffers of a pass built with `samples > 1`,
+    /// resolved into `textures` through `gl_resolve_fb` by `end_render_pass`
+    pub(crate) gl_msaa_rbs: Vec<GLuint>,
+    pub(crate) gl_resolve_fb: GLuint,
+    pub(crate) size: (u32, u32),
+    /// `GL_COLOR_ATTACHMENT0 + n` of every color attachment, empty for depth only passes
+    pub(crate) draw_buffers: Vec<GLenum>,
+}
+
+impl RenderPassInternal {
+    pub(crate) fn delete_gl_objects(&self) {
+        unsafe {
+            glDeleteFramebuffers(1, &self.gl_fb);
+            if self.gl_depth_rb != 0 {
+                glDeleteRenderbuffers(1, &self.gl_depth_rb);
+            }
+            if self.gl_resolve_fb != 0 {
+                glDeleteFramebuffers(1, &self.gl_resolve_fb);
+                glDeleteRenderbuffers(self.gl_msaa_rbs.len() as i32, self.gl_msaa_rbs.as_ptr());
+            }
+        }
+    }
+
+    /// Copy every multisampled color renderbuffer into its texture
+    fn resolve(&self) {
+        let (w, h) = (self.size.0 as i32, self.size.1 as i32);
+        unsafe {
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, self.gl_fb);
+            glBindFramebuffer(GL_DRAW_FRAMEBUFFER, self.gl_resolve_fb);
+            for n in 0..self.textures.len() {
+                let attachment = GL_COLOR_ATTACHMENT0 + n as GLenum;
+                // draw buffers are positional, only slot `n` may be active
+                let mut draw_buffers = vec![GL_NONE; n + 1];
+                draw_buffers[n] = attachment;
+                glReadBuffer(attachment);
+                glDrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr());
+                glBlitFramebuffer(0, 0, w, h, 0, 0, w, h, GL_COLOR_BUFFER_BIT, GL_NEAREST);
+            }
+            glReadBuffer(GL_COLOR_ATTACHMENT0);
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct RenderPass(pub(crate) usize);
+pub struct RenderPass(pub(crate) SlotId);
 
 impl RenderPass {
+    /// Errors like an incomplete framebuffer are only logged,
+    /// use `RenderPassBuilder` to handle them.
     pub fn new(
         ctx: &mut GraphicsContext,
         color_img: Texture,
         depth_img: impl Into<Option<Texture>>,
     ) -> RenderPass {
+        let mut builder = RenderPassBuilder::new().color(color_img);
+        if let Some(depth_img) = depth_img.into() {
+            builder = builder.depth(depth_img);
+        }
+        builder.build_or_log(ctx)
+    }
+
+    /// Pass rendering to all `color_imgs` at once, `gl_FragData[n]` goes to `color_imgs[n]`.
+    /// Fails when the GPU supports fewer color attachments or draw buffers than given textures.
+    pub fn new_mrt(
+        ctx: &mut GraphicsContext,
+        color_imgs: Vec<Texture>,
+        depth_img: impl Into<Option<Texture>>,
+    ) -> Result<RenderPass, RenderPassError> {
+        let mut builder = RenderPassBuilder::new();
+        for color_img in color_imgs {
+            builder = builder.color(color_img);
+        }
+        if let Some(depth_img) = depth_img.into() {
+            builder = builder.depth(depth_img);
+        }
+        builder.build(ctx)
+    }
+
+    /// Like `new`, but depth goes to a renderbuffer owned by the pass instead of a texture.
+    /// Cheaper when depth is never sampled, the renderbuffer is freed by `delete`.
+    pub fn new_with_depthbuffer(
+        ctx: &mut GraphicsContext,
+        color_img: Texture,
+        depth_format: DepthFormat,
+    ) -> RenderPass {
+        RenderPassBuilder::new()
+            .color(color_img)
+            .depth_renderbuffer(depth_format)
+            .build_or_log(ctx)
+    }
+
+    /// Render to the depth slice `z` of `color_img`, e.g. to fill a volume slice by slice.
+    ///
+    /// Attached with `glFramebufferTextureLayer`, the form of `glFramebufferTexture3D`
+    /// available on both GL 3.0 and GLES 3.0.
+    ///
+    /// NOTE: `features.texture_3d` check is required.
+    pub fn new_3d_slice(
+        ctx: &mut GraphicsContext,
+        color_img: Texture3D,
+        z: i32,
+        depth_img: impl Into<Option<Texture>>,
+    ) -> RenderPass {
+        assert!(
+            z >= 0 && (z as u32) < color_img.depth,
+            "Slice {} is out of the 3D texture depth {}",
+            z,
+            color_img.depth
+        );
+
         let mut gl_fb = 0;
 
         let depth_img = depth_img.into();
@@ -46,14 +207,14 @@ impl RenderPass {
         unsafe {
             glGenFramebuffers(1, &mut gl_fb as *mut _);
             glBindFramebuffer(GL_FRAMEBUFFER, gl_fb);
-            glFramebufferTexture2D(
+            glFramebufferTextureLayer(
                 GL_FRAMEBUFFER,
                 GL_COLOR_ATTACHMENT0,
-                GL_TEXTURE_2D,
                 color_img.texture,
                 0,
+                z,
             );
-            if let Some(depth_img) = depth_img.clone() {
+            if let Some(depth_img) = &depth_img {
                 glFramebufferTexture2D(
                     GL_FRAMEBUFFER,
                     GL_DEPTH_ATTACHMENT,
@@ -62,30 +223,465 @@ impl RenderPass {
                     0,
                 );
             }
-            glBindFramebuffer(GL_FRAMEBUFFER, ctx.default_framebuffer);
+            glBindFramebuffer(GL_FRAMEBUFFER, ctx.cache.framebuffer);
         }
         let pass = RenderPassInternal {
             gl_fb,
-            texture: color_img,
-            // 拿着所有权防止被drop
+            textures: vec![],
             _depth_texture: depth_img,
+            gl_depth_rb: 0,
+            gl_msaa_rbs: vec![],
+            gl_resolve_fb: 0,
+            size: (color_img.width, color_img.height),
+            texture_3d: Some(color_img),
+            draw_buffers: vec![GL_COLOR_ATTACHMENT0],
         };
 
-        ctx.passes.push(pass);
-
-        RenderPass(ctx.passes.len() - 1)
+        RenderPass(ctx.passes.insert(pass))
     }
 
+    /// First color texture of the pass.
+    /// Panics for depth-only and 3D slice passes.
     pub fn texture(&self, ctx: &mut GraphicsContext) -> Texture {
         let render_pass = &mut ctx.passes[self.0];
 
-        render_pass.texture.clone()
+        assert!(
+            render_pass.texture_3d.is_none(),
+            "3D slice passes have no 2D color texture"
+        );
+        assert!(
+            !render_pass.textures.is_empty(),
+            "depth-only pass has no color texture"
+        );
+        render_pass.textures[0].clone()
     }
 
+    /// Format of the (first) color attachment.
+    /// Panics for depth-only passes.
+    pub fn color_format(&self, ctx: &GraphicsContext) -> TextureFormat {
+        let render_pass = &ctx.passes[self.0];
+        match &render_pass.texture_3d {
+            Some(texture) => texture.format,
+            None => {
+                assert!(
+                    !render_pass.textures.is_empty(),
+                    "depth-only pass has no color texture"
+                );
+                render_pass.textures[0].format
+            }
+        }
+    }
+
+    /// Number of color attachments of the pass framebuffer
+    pub fn num_color_attachments(&self, ctx: &GraphicsContext) -> usize {
+        ctx.passes[self.0].draw_buffers.len()
+    }
+
+    /// Read a single RGBA8 pixel of the first color attachment, e.g. for object picking.
+    /// `x` and `y` are in framebuffer coordinates, with the origin at the bottom left.
+    ///
+    /// `RGBA8UI` attachments are read as is, `R32UI` ones as the native endian bytes of the
+    /// value, see `u32::from_ne_bytes`.
+    pub fn read_pixel(&self, ctx: &GraphicsContext, x: i32, y: i32) -> [u8; 4] {
+        self.read_pixel_from(ctx, 0, x, y)
+    }
+
+    /// Like `read_pixel`, but reads color attachment `attachment`, e.g. an ID attachment of an MRT pass.
+    /// Multisampled passes are read from their textures, resolved by the last `end_render_pass`.
+    pub fn read_pixel_from(
+        &self,
+        ctx: &GraphicsContext,
+        attachment: u32,
+        x: i32,
+        y: i32,
+    ) -> [u8; 4] {
+        assert!(
+            (attachment as usize) < self.num_color_attachments(ctx),
+            "RenderPass has no color attachment {}",
+            attachment
+        );
+        let render_pass = &ctx.passes[self.0];
+        let format = match &render_pass.texture_3d {
+            Some(texture) => texture.format,
+            None => render_pass.textures[attachment as usize].format,
+        };
+        // integer color buffers can only be read with *_INTEGER formats, both are 4 bytes per pixel
+        let (format, pixel_type) = if format.is_integer() {
+            let (_, format, pixel_type) = format.into();
+            (format, pixel_type)
+        } else {
+            (GL_RGBA, GL_UNSIGNED_BYTE)
+        };
+        let mut pixel = [0u8; 4];
+
+        unsafe {
+            let mut binded_fbo: i32 = 0;
+            glGetIntegerv(GL_READ_FRAMEBUFFER_BINDING, &mut binded_fbo);
+            // the multisampled framebuffer can't be read directly
+            let gl_fb = match render_pass.gl_resolve_fb {
+                0 => render_pass.gl_fb,
+                resolve_fb => resolve_fb,
+            };
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, gl_fb);
+            glReadBuffer(GL_COLOR_ATTACHMENT0 + attachment);
+            glReadPixels(x, y, 1, 1, format, pixel_type, pixel.as_mut_ptr() as *mut _);
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, binded_fbo as _);
+        }
+
+        pixel
+    }
+
+    /// Actual number of samples of the pass framebuffer, as reported by the driver.
+    /// 0 for a framebuffer without multisampling.
+    pub fn sample_count(&self, ctx: &GraphicsContext) -> i32 {
+        let render_pass = &ctx.passes[self.0];
+        let mut samples = 0;
+
+        unsafe {
+            let mut binded_fbo: i32 = 0;
+            glGetIntegerv(GL_DRAW_FRAMEBUFFER_BINDING, &mut binded_fbo);
+            glBindFramebuffer(GL_DRAW_FRAMEBUFFER, render_pass.gl_fb);
+            glGetIntegerv(GL_SAMPLES, &mut samples);
+            glBindFramebuffer(GL_DRAW_FRAMEBUFFER, binded_fbo as _);
+        }
+
+        samples
+    }
+
+    /// Delete the framebuffer and the depth renderbuffer of `new_with_depthbuffer` passes,
+    /// and free the pass slot. Using the pass afterwards panics.
+    ///
+    /// The pass holds clones of its color and depth textures, they are released here
+    /// and deleted once the caller drops its own clones too.
     pub fn delete(&self, ctx: &mut GraphicsContext) {
-        let render_pass = &mut ctx.passes[self.0];
+        let render_pass = ctx
+            .passes
+            .remove(self.0)
+            .expect("RenderPass is already deleted");
 
-        unsafe { glDeleteFramebuffers(1, &mut render_pass.gl_fb as *mut _) }
+        render_pass.delete_gl_objects();
+    }
+}
+
+/// Framebuffer description for [`RenderPass`], covering every attachment combination
+/// of the `RenderPass::new*` constructors.
+/// ```ignore
+/// let pass = RenderPassBuilder::new()
+///     .color(albedo)
+///     .color(normals)
+///     .depth_renderbuffer(DepthFormat::Depth24)
+///     .samples(4)
+///     .build(ctx)?;
+/// ```
+/// Textures given to the builder are owned by the built pass, see [`RenderPass::delete`].
+#[derive(Default)]
+pub struct RenderPassBuilder {
+    colors: Vec<(Texture, i32)>,
+    depth: Option<Texture>,
+    depth_renderbuffer: Option<DepthFormat>,
+    samples: i32,
+}
+
+impl RenderPassBuilder {
+    pub fn new() -> RenderPassBuilder {
+        RenderPassBuilder::default()
+    }
+
+    /// Next color attachment, `gl_FragData[n]` goes to the `n`-th added texture
+    pub fn color(self, texture: Texture) -> Self {
+        self.color_mip(texture, 0)
+    }
+
+    /// Like `color`, but renders to the mip `level` of `texture`
+    pub fn color_mip(mut self, texture: Texture, level: i32) -> Self {
+        self.colors.push((texture, level));
+        self
+    }
+
+    /// Depth texture, e.g. for shadow maps. Without color attachments the pass is depth only.
+    pub fn depth(mut self, texture: Texture) -> Self {
+        self.depth = Some(texture);
+        self
+    }
+
+    /// Depth renderbuffer owned by the pass, cheaper than `depth` when depth is never sampled
+    pub fn depth_renderbuffer(mut self, format: DepthFormat) -> Self {
+        self.depth_renderbuffer = Some(format);
+        self
+    }
+
+    /// Render to multisampled renderbuffers, resolved into the color textures
+    /// by [`GraphicsContext::end_render_pass`]. 0 and 1 disable multisampling.
+    pub fn samples(mut self, samples: i32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn build(self, ctx: &mut GraphicsContext) -> Result<RenderPass, RenderPassError> {
+        let size = self.validate(ctx)?;
+        let (pass, status) = self.create(ctx, size);
+        if status != GL_FRAMEBUFFER_COMPLETE {
+            pass.delete_gl_objects();
+            return Err(RenderPassError::Incomplete(status));
+        }
+
+        Ok(RenderPass(ctx.passes.insert(pass)))
+    }
+
+    /// Like `build`, but errors are logged and the pass is created anyway,
+    /// for the constructors of `RenderPass` that can't fail.
+    fn build_or_log(self, ctx: &mut GraphicsContext) -> RenderPass {
+        let size = match self.validate(ctx) {
+            Ok(size) => size,
+            Err(err) => {
+                log::error!("Invalid render pass: {}", err);
+                self.colors
+                    .first()
+                    .map(|(texture, _)| (texture.width, texture.height))
+                    .unwrap_or((0, 0))
+            }
+        };
+        let (pass, status) = self.create(ctx, size);
+        if status != GL_FRAMEBUFFER_COMPLETE {
+            log::error!("Render pass framebuffer is incomplete: {:#x}", status);
+        }
+        RenderPass(ctx.passes.insert(pass))
+    }
+
+    /// Framebuffer and attachments of `size`, with the `glCheckFramebufferStatus` result
+    fn create(self, ctx: &mut GraphicsContext, size: (u32, u32)) -> (RenderPassInternal, GLenum) {
+        let multisampled = self.samples > 1;
+        let (w, h) = (size.0 as i32, size.1 as i32);
+
+        let mut gl_fb = 0;
+        let mut gl_depth_rb = 0;
+        let mut gl_msaa_rbs = vec![0; if multisampled { self.colors.len() } else { 0 }];
+        let mut gl_resolve_fb = 0;
+
+        let draw_buffers: Vec<GLenum> = (0..self.colors.len() as GLenum)
+            .map(|n| GL_COLOR_ATTACHMENT0 + n)
+            .collect();
+
+        let status = unsafe {
+            glGenFramebuffers(1, &mut gl_fb as *mut _);
+            glBindFramebuffer(GL_FRAMEBUFFER, gl_fb);
+            if multisampled {
+                glGenRenderbuffers(gl_msaa_rbs.len() as i32, gl_msaa_rbs.as_mut_ptr());
+                for ((texture, _), (&rb, &attachment)) in self
+                    .colors
+                    .iter()
+                    .zip(gl_msaa_rbs.iter().zip(&draw_buffers))
+                {
+                    glBindRenderbuffer(GL_RENDERBUFFER, rb);
+                    glRenderbufferStorageMultisample(
+                        GL_RENDERBUFFER,
+                        self.samples,
+                        texture.format.renderbuffer_format(),
+                        w,
+                        h,
+                    );
+                    glFramebufferRenderbuffer(GL_FRAMEBUFFER, attachment, GL_RENDERBUFFER, rb);
+                }
+            } else {
+                attach_textures(&self.colors, &draw_buffers);
+            }
+            if let Some(depth_img) = &self.depth {
+                glFramebufferTexture2D(
+                    GL_FRAMEBUFFER,
+                    GL_DEPTH_ATTACHMENT,
+                    GL_TEXTURE_2D,
+                    depth_img.texture,
+                    0,
+                );
+            }
+            if let Some(depth_format) = self.depth_renderbuffer {
+                let (internal_format, attachment) = depth_format.gl_format();
+                glGenRenderbuffers(1, &mut gl_depth_rb as *mut _);
+                glBindRenderbuffer(GL_RENDERBUFFER, gl_depth_rb);
+                if multisampled {
+                    glRenderbufferStorageMultisample(
+                        GL_RENDERBUFFER,
+                        self.samples,
+                        internal_format,
+                        w,
+                        h,
+                    );
+                } else {
+                    glRenderbufferStorage(GL_RENDERBUFFER, internal_format, w, h);
+                }
+                glFramebufferRenderbuffer(GL_FRAMEBUFFER, attachment, GL_RENDERBUFFER, gl_depth_rb);
+            }
+            glBindRenderbuffer(GL_RENDERBUFFER, 0);
+            if draw_buffers.is_empty() {
+                // depth only
+                glDrawBuffers(1, &GL_NONE);
+                glReadBuffer(GL_NONE);
+            } else {
+                glDrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr());
+            }
+            let status = glCheckFramebufferStatus(GL_FRAMEBUFFER);
+
+            if multisampled {
+                glGenFramebuffers(1, &mut gl_resolve_fb as *mut _);
+                glBindFramebuffer(GL_FRAMEBUFFER, gl_resolve_fb);
+                attach_textures(&self.colors, &draw_buffers);
+            }
+            glBindFramebuffer(GL_FRAMEBUFFER, ctx.cache.framebuffer);
+            status
+        };
+
+        let pass = RenderPassInternal {
+            gl_fb,
+            textures: self
+                .colors
+                .into_iter()
+                .map(|(texture, _)| texture)
+                .collect(),
+            _depth_texture: self.depth,
+            gl_depth_rb,
+            texture_3d: None,
+            draw_buffers,
+            gl_msaa_rbs,
+            gl_resolve_fb,
+            size,
+        };
+        (pass, status)
+    }
+
+    /// Size of the framebuffer
+    fn validate(&self, ctx: &GraphicsContext) -> Result<(u32, u32), RenderPassError> {
+        if self.depth.is_some() && self.depth_renderbuffer.is_some() {
+            return Err(RenderPassError::ConflictingDepth);
+        }
+
+        let max = ctx
+            .max_draw_buffers()
+            .min(ctx.max_color_attachments())
+            .max(1) as usize;
+        if self.colors.len() > max {
+            return Err(RenderPassError::TooManyColorAttachments {
+                count: self.colors.len(),
+                max,
+            });
+        }
+
+        if self.samples > 1 {
+            if self.depth.is_some() {
+                return Err(RenderPassError::MultisampleDepthTexture);
+            }
+            let mut max = 0;
+            unsafe { glGetIntegerv(GL_MAX_SAMPLES, &mut max) };
+            if self.samples > max {
+                return Err(RenderPassError::TooManySamples {
+                    samples: self.samples,
+                    max,
+                });
+            }
+        }
+
+        let mut sizes = self
+            .colors
+            .iter()
+            .map(|(texture, level)| {
+                (
+                    (texture.width >> level).max(1),
+                    (texture.height >> level).max(1),
+                )
+            })
+            .chain(self.depth.iter().map(|depth| (depth.width, depth.height)));
+        let expected = sizes.next().ok_or(RenderPassError::NoAttachments)?;
+        match sizes.find(|&size| size != expected) {
+            Some(found) => Err(RenderPassError::SizeMismatch { expected, found }),
+            None => Ok(expected),
+        }
+    }
+}
+
+/// Attach `colors` to the bound framebuffer
+unsafe fn attach_textures(colors: &[(Texture, i32)], attachments: &[GLenum]) {
+    for ((texture, level), attachment) in colors.iter().zip(attachments) {
+        glFramebufferTexture2D(
+            GL_FRAMEBUFFER,
+            *attachment,
+            GL_TEXTURE_2D,
+            texture.texture,
+            *level,
+        );
+    }
+}
+
+/// Offscreen pass following the window size, e.g. for post-processing.
+/// ```ignore
+/// let mut pass = ResizablePass::new(ctx, TextureParams { width, height, ..Default::default() }, Some(DepthFormat::Depth24));
+///
+/// // on WindowEvent::FramebufferSize(w, h)
+/// pass.resize(ctx, w as u32, h as u32);
+///
+/// ctx.begin_pass(pass.pass(), PassAction::default());
+/// ```
+pub struct ResizablePass {
+    params: TextureParams,
+    depth_format: Option<DepthFormat>,
+    pass: RenderPass,
+}
+
+impl ResizablePass {
+    /// Color texture created with `params`, depth renderbuffer of `depth_format` if any
+    pub fn new(
+        ctx: &mut GraphicsContext,
+        params: TextureParams,
+        depth_format: Option<DepthFormat>,
+    ) -> ResizablePass {
+        ResizablePass {
+            params,
+            depth_format,
+            pass: Self::create(ctx, params, depth_format),
+        }
+    }
+
+    fn create(
+        ctx: &mut GraphicsContext,
+        params: TextureParams,
+        depth_format: Option<DepthFormat>,
+    ) -> RenderPass {
+        let color_img = Texture::new_render_texture(ctx, params);
+        match depth_format {
+            Some(depth_format) => RenderPass::new_with_depthbuffer(ctx, color_img, depth_format),
+            None => RenderPass::new(ctx, color_img, None),
+        }
+    }
+
+    /// Delete the framebuffer, texture and renderbuffer and create new ones of `width`x`height`.
+    /// No-op when the size didn't change, or is 0 like the size of a minimized window.
+    pub fn resize(&mut self, ctx: &mut GraphicsContext, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        if self.params.width == width && self.params.height == height {
+            return;
+        }
+        self.pass.delete(ctx);
+        self.params.width = width;
+        self.params.height = height;
+        self.pass = Self::create(ctx, self.params, self.depth_format);
+    }
+
+    /// Current pass, changes on every `resize`
+    pub fn pass(&self) -> RenderPass {
+        self.pass
+    }
+
+    pub fn texture(&self, ctx: &mut GraphicsContext) -> Texture {
+        self.pass.texture(ctx)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.params.width, self.params.height)
+    }
+
+    /// Delete the current pass with all its GL objects
+    pub fn delete(&self, ctx: &mut GraphicsContext) {
+        self.pass.delete(ctx);
     }
 }
 
@@ -97,6 +693,9 @@ impl GraphicsContext {
     }
 
     /// start rendering to an offscreen framebuffer
+    ///
+    /// Passes can be nested, `end_render_pass` returns to the framebuffer and viewport
+    /// of the enclosing pass.
     pub fn begin_pass(
         &mut self,
         pass: impl Into<Option<RenderPass>>,
@@ -113,37 +712,118 @@ impl GraphicsContext {
             }
             Some(pass) => {
                 let pass = &self.passes[pass.0];
-                (
-                    pass.gl_fb,
-                    pass.texture.width as i32,
-                    pass.texture.height as i32,
-                )
+                let (w, h) = pass.size;
+                (pass.gl_fb, w as i32, h as i32)
             }
         };
-        unsafe {
-            glBindFramebuffer(GL_FRAMEBUFFER, framebuffer);
-            glViewport(0, 0, w, h);
-            glScissor(0, 0, w, h);
-        }
+        // draw buffers are framebuffer state, switch the enclosing framebuffer back on
+        // before leaving it, the new pass then starts with all attachments written
+        self.set_color_output(true);
+        self.pass_stack.push((framebuffer, w, h));
+        self.bind_pass_framebuffer(framebuffer, w, h);
         match action {
             PassAction::Nothing => {}
             PassAction::Clear(clear) => {
-                clear.apply();
+                self.clear(clear);
             }
         }
+        self.apply_persistent_scissor();
         self
     }
 
     pub fn end_render_pass(&mut self) -> &mut Self {
-        unsafe {
-            glBindFramebuffer(GL_FRAMEBUFFER, self.default_framebuffer);
-            self.cache.bind_buffer(GL_ARRAY_BUFFER, 0, None);
-            self.cache.bind_buffer(GL_ELEMENT_ARRAY_BUFFER, 0, None);
+        self.set_color_output(true);
+        if let Some((framebuffer, _, _)) = self.pass_stack.pop() {
+            if let Some(pass) = self
+                .passes
+                .iter()
+                .find(|pass| pass.gl_fb == framebuffer && pass.gl_resolve_fb != 0)
+            {
+                pass.resolve();
+            }
         }
+        // a nested pass returns to the enclosing one
+        if let Some(&(framebuffer, w, h)) = self.pass_stack.last() {
+            self.bind_pass_framebuffer(framebuffer, w, h);
+            self.apply_persistent_scissor();
+        } else {
+            self.cache.framebuffer = self.default_framebuffer;
+            unsafe { glBindFramebuffer(GL_FRAMEBUFFER, self.default_framebuffer) };
+        }
+        self.cache.bind_buffer(GL_ARRAY_BUFFER, 0, None);
+        self.cache.bind_buffer(GL_ELEMENT_ARRAY_BUFFER, 0, None);
         self
     }
 
+    fn bind_pass_framebuffer(&mut self, framebuffer: GLuint, w: i32, h: i32) {
+        self.cache.framebuffer = framebuffer;
+        unsafe {
+            glBindFramebuffer(GL_FRAMEBUFFER, framebuffer);
+        }
+        self.apply_viewport(0, 0, w, h);
+        self.apply_scissor_rect(0, 0, w, h);
+    }
+
+    /// Number of color outputs a fragment shader can write at once
+    pub fn max_draw_buffers(&self) -> i32 {
+        let mut max = 0;
+        unsafe { glGetIntegerv(GL_MAX_DRAW_BUFFERS, &mut max) };
+        max
+    }
+
+    /// Number of color textures a framebuffer can have attached
+    pub fn max_color_attachments(&self) -> i32 {
+        let mut max = 0;
+        unsafe { glGetIntegerv(GL_MAX_COLOR_ATTACHMENTS, &mut max) };
+        max
+    }
+
+    /// Framebuffer bound by the last `begin_pass`/`end_render_pass`,
+    /// `default_framebuffer` outside of passes.
+    pub fn current_framebuffer(&self) -> GLuint {
+        self.cache.framebuffer
+    }
+
+    /// Read the default framebuffer as RGBA8, `width * height * 4` bytes for the framebuffer size
+    /// of the window, which is larger than its screen size on HiDPI displays.
+    ///
+    /// Rows are flipped to start with the top row, as image files expect.
+    /// GL itself reads rows bottom-up, so raw `glReadPixels` data would be upside down.
+    pub fn screenshot(&mut self) -> Vec<u8> {
+        let (width, height) = self.window().get_framebuffer_size();
+        let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+        let mut pixels = vec![0u8; width * height * 4];
+
+        unsafe {
+            let mut pack_alignment = 4;
+            glGetIntegerv(GL_PACK_ALIGNMENT, &mut pack_alignment);
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, self.default_framebuffer);
+            glPixelStorei(GL_PACK_ALIGNMENT, 1);
+            glReadPixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+            glPixelStorei(GL_PACK_ALIGNMENT, pack_alignment);
+            glBindFramebuffer(GL_READ_FRAMEBUFFER, self.cache.framebuffer);
+        }
+
+        let row = width * 4;
+        pixels
+            .chunks_exact(row.max(1))
+            .rev()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
     pub fn commit_frame(&mut self) {
+        self.pass_stack.clear();
+        next_frame();
         self.cache.clear_buffer_bindings();
         self.cache.clear_texture_bindings();
     }
@@ -36,6 +36,15 @@ pub enum VertexFormat {
     Int4,
     /// Four by four matrix of 32-bit floats
     Mat4,
+    /// Two unsigned 8-bit integers (equivalent to `[u8; 2]`), read as floats in `0..=1`
+    Byte2Norm,
+    /// Four unsigned 8-bit integers (equivalent to `[u8; 4]`), read as floats in `0..=1`,
+    /// e.g. packed vertex colors
+    Byte4Norm,
+    /// Two unsigned 16-bit integers (equivalent to `[u16; 2]`), read as floats in `0..=1`
+    Short2Norm,
+    /// Four unsigned 16-bit integers (equivalent to `[u16; 4]`), read as floats in `0..=1`
+    Short4Norm,
 }
 
 impl VertexFormat {
@@ -58,6 +67,10 @@ impl VertexFormat {
             VertexFormat::Int3 => 3,
             VertexFormat::Int4 => 4,
             VertexFormat::Mat4 => 16,
+            VertexFormat::Byte2Norm => 2,
+            VertexFormat::Byte4Norm => 4,
+            VertexFormat::Short2Norm => 2,
+            VertexFormat::Short4Norm => 4,
         }
     }
 
@@ -80,6 +93,10 @@ impl VertexFormat {
             VertexFormat::Int3 => 3 * 4,
             VertexFormat::Int4 => 4 * 4,
             VertexFormat::Mat4 => 16 * 4,
+            VertexFormat::Byte2Norm => 2,
+            VertexFormat::Byte4Norm => 4,
+            VertexFormat::Short2Norm => 2 * 2,
+            VertexFormat::Short4Norm => 4 * 2,
         }
     }
 
@@ -102,8 +119,23 @@ impl VertexFormat {
             VertexFormat::Int3 => GL_UNSIGNED_INT,
             VertexFormat::Int4 => GL_UNSIGNED_INT,
             VertexFormat::Mat4 => GL_FLOAT,
+            VertexFormat::Byte2Norm => GL_UNSIGNED_BYTE,
+            VertexFormat::Byte4Norm => GL_UNSIGNED_BYTE,
+            VertexFormat::Short2Norm => GL_UNSIGNED_SHORT,
+            VertexFormat::Short4Norm => GL_UNSIGNED_SHORT,
         }
     }
+
+    /// Integer data mapped to `0..=1` floats by `glVertexAttribPointer`
+    pub(crate) fn normalized(&self) -> bool {
+        matches!(
+            self,
+            VertexFormat::Byte2Norm
+                | VertexFormat::Byte4Norm
+                | VertexFormat::Short2Norm
+                | VertexFormat::Short4Norm
+        )
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -195,6 +227,26 @@ pub enum CullFace {
     Back,
 }
 
+/// How polygons are rasterized, see [`GraphicsContext::set_polygon_mode`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PolygonMode {
+    Fill,
+    /// Wireframe, only the edges are drawn
+    Line,
+    /// Only the vertices are drawn
+    Point,
+}
+
+impl From<PolygonMode> for GLenum {
+    fn from(mode: PolygonMode) -> GLenum {
+        match mode {
+            PolygonMode::Fill => GL_FILL,
+            PolygonMode::Line => GL_LINE,
+            PolygonMode::Point => GL_POINT,
+        }
+    }
+}
+
 /// Define front- and back-facing polygons.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum FrontFaceOrder {
@@ -206,9 +258,14 @@ pub enum FrontFaceOrder {
 pub struct PipelineConf {
     pub cull_face: CullFace,
     pub front_face_order: FrontFaceOrder,
+    /// The depth test is disabled only for `Always` without `depth_write`
     pub depth_test: Comparison,
+    /// Independent of `depth_test`, e.g. `Less` without writes for transparent geometry
     pub depth_write: bool,
     pub depth_write_offset: Option<(f32, f32)>,
+    /// Clamp depth instead of clipping against near/far planes.
+    /// Ignored when `features.depth_clamp` is false.
+    pub depth_clamp: bool,
     /// Color (RGB) blend function. If None - blending will be disabled for this pipeline.
     /// Usual use case to get alpha-blending:
     ///```
@@ -248,6 +305,13 @@ pub struct PipelineConf {
     pub stencil_test: Option<StencilState>,
     pub color_write: ColorMask,
     pub primitive_type: PrimitiveType,
+    /// Draw the edges of the triangles again on top of the filled geometry, e.g. for debug views.
+    /// The shader's `uniform vec4 wireframe_color;` (if declared) is set to the given color for
+    /// the edges and to transparent black for the filled draw.
+    ///
+    /// Costs an extra draw call for every `draw`/`draw_arrays`.
+    /// NOTE: ignored when `features.polygon_mode` is false.
+    pub wireframe_overlay: Option<Color>,
 }
 
 impl Default for PipelineConf {
@@ -258,11 +322,13 @@ impl Default for PipelineConf {
             depth_test: Comparison::Always, // no depth test,
             depth_write: false,             // no depth write,
             depth_write_offset: None,
+            depth_clamp: false,
             color_blend: None,
             alpha_blend: None,
             stencil_test: None,
             color_write: (true, true, true, true),
             primitive_type: PrimitiveType::Triangles,
+            wireframe_overlay: None,
         }
     }
 }
@@ -272,6 +338,7 @@ pub(crate) struct VertexAttributeInternal {
     pub(crate) attr_loc: GLuint,
     pub(crate) size: i32,
     pub(crate) type_: GLuint,
+    pub(crate) normalized: bool,
     pub(crate) offset: i64,
     pub(crate) stride: i32,
     pub(crate) buffer_index: usize,
@@ -280,12 +347,14 @@ pub(crate) struct VertexAttributeInternal {
 
 pub(crate) struct PipelineInternal {
     pub(crate) layout: Vec<Option<VertexAttributeInternal>>,
+    pub(crate) buffer_layout: Vec<BufferLayout>,
+    pub(crate) attributes: Vec<VertexAttribute>,
     pub(crate) shader: Shader,
     pub(crate) params: PipelineConf,
 }
 
 #[derive(Copy, Clone, Debug)]
-pub struct Pipeline(pub(crate) usize);
+pub struct Pipeline(pub(crate) SlotId);
 
 impl Pipeline {
     pub fn new(
@@ -304,108 +373,47 @@ impl Pipeline {
         shader: Shader,
         params: PipelineConf,
     ) -> Pipeline {
-        #[derive(Clone, Copy, Default)]
-        struct BufferCacheData {
-            stride: i32,
-            offset: i64,
-        }
-
-        let mut buffer_cache: Vec<BufferCacheData> =
-            vec![BufferCacheData::default(); buffer_layout.len()];
-
-        for VertexAttribute {
-            format,
-            buffer_index,
-            ..
-        } in attributes
-        {
-            let layout = buffer_layout.get(*buffer_index).unwrap_or_else(|| panic!());
-            let mut cache = buffer_cache
-                .get_mut(*buffer_index)
-                .unwrap_or_else(|| panic!());
-
-            if layout.stride == 0 {
-                cache.stride += format.byte_len();
-            } else {
-                cache.stride = layout.stride;
-            }
-            // WebGL 1 limitation
-            assert!(cache.stride <= 255);
-        }
-
         let program = ctx.shaders[shader.0].program;
+        let vertex_layout = vertex_layout(program, buffer_layout, attributes);
 
-        let attributes_len = attributes
-            .iter()
-            .map(|layout| match layout.format {
-                VertexFormat::Mat4 => 4,
-                _ => 1,
-            })
-            .sum();
-
-        let mut vertex_layout: Vec<Option<VertexAttributeInternal>> = vec![None; attributes_len];
-
-        for VertexAttribute {
-            name,
-            format,
-            buffer_index,
-        } in attributes
-        {
-            let mut buffer_data = &mut buffer_cache
-                .get_mut(*buffer_index)
-                .unwrap_or_else(|| panic!());
-            let layout = buffer_layout.get(*buffer_index).unwrap_or_else(|| panic!());
-
-            let cname = CString::new(*name).unwrap_or_else(|e| panic!("{}", e));
-            let attr_loc = unsafe { glGetAttribLocation(program, cname.as_ptr() as *const _) };
-            let attr_loc = if attr_loc == -1 { None } else { Some(attr_loc) };
-            let divisor = if layout.step_func == VertexStep::PerVertex {
-                0
-            } else {
-                layout.step_rate
-            };
-
-            let mut attributes_count: usize = 1;
-            let mut format = *format;
-
-            if format == VertexFormat::Mat4 {
-                format = VertexFormat::Float4;
-                attributes_count = 4;
-            }
-            for i in 0..attributes_count {
-                if let Some(attr_loc) = attr_loc {
-                    let attr_loc = attr_loc as GLuint + i as GLuint;
-
-                    let attr = VertexAttributeInternal {
-                        attr_loc,
-                        size: format.size(),
-                        type_: format.type_(),
-                        offset: buffer_data.offset,
-                        stride: buffer_data.stride,
-                        buffer_index: *buffer_index,
-                        divisor,
-                    };
-
-                    assert!(
-                        attr_loc < vertex_layout.len() as u32,
-                        "attribute: {} outside of allocated attributes array len: {}",
-                        name,
-                        vertex_layout.len()
-                    );
-                    vertex_layout[attr_loc as usize] = Some(attr);
-                }
-                buffer_data.offset += format.byte_len() as i64
-            }
-        }
+        ctx.shaders[shader.0].pipelines += 1;
 
         let pipeline = PipelineInternal {
             layout: vertex_layout,
+            buffer_layout: buffer_layout.to_vec(),
+            attributes: attributes.to_vec(),
             shader,
             params,
         };
 
-        ctx.pipelines.push(pipeline);
-        Pipeline(ctx.pipelines.len() - 1)
+        Pipeline(ctx.pipelines.insert(pipeline))
+    }
+
+    /// Copy of the parameters the pipeline was created with
+    pub fn params(&self, ctx: &GraphicsContext) -> PipelineConf {
+        ctx.pipelines[self.0].params
+    }
+
+    /// Resolve attribute locations again against the current program of the pipeline's shader.
+    /// Required after the shader program was replaced, otherwise stale locations are used.
+    pub fn rebuild_layout(&self, ctx: &mut GraphicsContext) {
+        let pipeline = &ctx.pipelines[self.0];
+        let program = ctx.shaders[pipeline.shader.0].program;
+        let layout = vertex_layout(program, &pipeline.buffer_layout, &pipeline.attributes);
+        ctx.pipelines[self.0].layout = layout;
+    }
+
+    /// Free the pipeline slot and release its shader, using the pipeline afterwards panics.
+    pub fn delete(&self, ctx: &mut GraphicsContext) {
+        let pipeline = ctx
+            .pipelines
+            .remove(self.0)
+            .expect("Pipeline is already deleted");
+        ctx.shaders[pipeline.shader.0].pipelines -= 1;
+
+        if ctx.cache.cur_pipeline.map(|p| p.0) == Some(self.0) {
+            ctx.cache.cur_pipeline = None;
+        }
     }
 
     pub fn set_blend(&self, ctx: &mut GraphicsContext, color_blend: Option<BlendState>) {
@@ -414,49 +422,137 @@ impl Pipeline {
     }
 }
 
-impl GraphicsContext {
-    pub fn apply_pipeline(&mut self, pipeline: &Pipeline) {
-        self.cache.cur_pipeline = Some(*pipeline);
+/// Resolve attribute locations of `program` into a per-location attribute table.
+pub(crate) fn vertex_layout(
+    program: GLuint,
+    buffer_layout: &[BufferLayout],
+    attributes: &[VertexAttribute],
+) -> Vec<Option<VertexAttributeInternal>> {
+    #[derive(Clone, Copy, Default)]
+    struct BufferCacheData {
+        stride: i32,
+        offset: i64,
+    }
 
-        {
-            let pipeline = &self.pipelines[pipeline.0];
-            let shader = &mut self.shaders[pipeline.shader.0];
-            unsafe {
-                glUseProgram(shader.program);
-            }
+    let mut buffer_cache: Vec<BufferCacheData> =
+        vec![BufferCacheData::default(); buffer_layout.len()];
+
+    for VertexAttribute {
+        format,
+        buffer_index,
+        ..
+    } in attributes
+    {
+        let layout = buffer_layout.get(*buffer_index).unwrap_or_else(|| panic!());
+        let mut cache = buffer_cache
+            .get_mut(*buffer_index)
+            .unwrap_or_else(|| panic!());
+
+        if layout.stride == 0 {
+            cache.stride += format.byte_len();
+        } else {
+            cache.stride = layout.stride;
+        }
+        // WebGL 1 limitation
+        assert!(cache.stride <= 255);
+    }
 
-            unsafe {
-                glEnable(GL_SCISSOR_TEST);
-            }
+    let attributes_len = attributes
+        .iter()
+        .map(|layout| match layout.format {
+            VertexFormat::Mat4 => 4,
+            _ => 1,
+        })
+        .sum();
+
+    let mut vertex_layout: Vec<Option<VertexAttributeInternal>> = vec![None; attributes_len];
+
+    for VertexAttribute {
+        name,
+        format,
+        buffer_index,
+    } in attributes
+    {
+        let buffer_data = &mut buffer_cache
+            .get_mut(*buffer_index)
+            .unwrap_or_else(|| panic!());
+        let layout = buffer_layout.get(*buffer_index).unwrap_or_else(|| panic!());
+
+        let cname = CString::new(*name).unwrap_or_else(|e| panic!("{}", e));
+        let attr_loc = unsafe { glGetAttribLocation(program, cname.as_ptr() as *const _) };
+        let attr_loc = if attr_loc == -1 { None } else { Some(attr_loc) };
+        let divisor = if layout.step_func == VertexStep::PerVertex {
+            0
+        } else {
+            layout.step_rate
+        };
 
-            if pipeline.params.depth_write {
-                unsafe {
-                    glEnable(GL_DEPTH_TEST);
-                    glDepthFunc(pipeline.params.depth_test.into())
-                }
-            } else {
-                unsafe {
-                    glDisable(GL_DEPTH_TEST);
-                }
-            }
+        let mut attributes_count: usize = 1;
+        let mut format = *format;
 
-            match pipeline.params.front_face_order {
-                FrontFaceOrder::Clockwise => unsafe {
-                    glFrontFace(GL_CW);
-                },
-                FrontFaceOrder::CounterClockwise => unsafe {
-                    glFrontFace(GL_CCW);
-                },
+        if format == VertexFormat::Mat4 {
+            format = VertexFormat::Float4;
+            attributes_count = 4;
+        }
+        for i in 0..attributes_count {
+            if let Some(attr_loc) = attr_loc {
+                let attr_loc = attr_loc as GLuint + i as GLuint;
+
+                let attr = VertexAttributeInternal {
+                    attr_loc,
+                    size: format.size(),
+                    type_: format.type_(),
+                    normalized: format.normalized(),
+                    offset: buffer_data.offset,
+                    stride: buffer_data.stride,
+                    buffer_index: *buffer_index,
+                    divisor,
+                };
+
+                assert!(
+                    (attr_loc as usize) < MAX_VERTEX_ATTRIBUTES,
+                    "attribute: {} location {} exceeds MAX_VERTEX_ATTRIBUTES: {}",
+                    name,
+                    attr_loc,
+                    MAX_VERTEX_ATTRIBUTES
+                );
+                // explicitly bound locations may be sparse
+                if attr_loc as usize >= vertex_layout.len() {
+                    vertex_layout.resize(attr_loc as usize + 1, None);
+                }
+                vertex_layout[attr_loc as usize] = Some(attr);
             }
+            buffer_data.offset += format.byte_len() as i64
         }
+    }
+
+    vertex_layout
+}
 
-        self.set_cull_face(self.pipelines[pipeline.0].params.cull_face);
-        self.set_blend(
-            self.pipelines[pipeline.0].params.color_blend,
-            self.pipelines[pipeline.0].params.alpha_blend,
-        );
+impl GraphicsContext {
+    pub fn apply_pipeline(&mut self, pipeline: &Pipeline) {
+        self.cache.cur_pipeline = Some(*pipeline);
 
-        self.set_stencil(self.pipelines[pipeline.0].params.stencil_test);
-        self.set_color_write(self.pipelines[pipeline.0].params.color_write);
+        let params = self.pipelines[pipeline.0].params;
+        let program = self.shaders[self.pipelines[pipeline.0].shader.0].program;
+        self.cache.use_program(program);
+
+        self.cache.set_scissor_test(true);
+
+        self.cache.set_front_face(params.front_face_order);
+        // writes need the test enabled, `Always` passes every fragment
+        let depth_test = params.depth_test != Comparison::Always || params.depth_write;
+        self.set_depth_test(depth_test);
+        if depth_test {
+            self.set_depth_func(params.depth_test);
+        }
+        self.set_depth_write(params.depth_write);
+        self.set_cull_face(params.cull_face);
+        self.set_blend(params.color_blend, params.alpha_blend);
+
+        self.set_stencil(params.stencil_test);
+        self.set_color_write(params.color_write);
+        self.set_depth_clamp(params.depth_clamp);
+        self.set_depth_write_offset(params.depth_write_offset);
     }
 }
@@ -21,6 +21,11 @@ pub enum ShaderError {
     LinkError(String),
     /// Shader strings should never contains \00 in the middle
     FFINulError(std::ffi::NulError),
+    /// The shader can't be deleted while this many pipelines still use it
+    InUse(usize),
+    /// Uniform and image names of `ShaderMeta` not found in the linked program,
+    /// misspelled or optimized out by the driver
+    MissingLocations(Vec<String>),
 }
 
 impl From<std::ffi::NulError> for ShaderError {
@@ -42,20 +47,34 @@ impl Error for ShaderError {
 }
 
 #[derive(Clone, Debug, Copy)]
-pub struct Shader(pub(crate) usize);
+pub struct Shader(pub(crate) SlotId);
 
 fn load_shader_internal(
     vertex_shader: &str,
     fragment_shader: &str,
     meta: ShaderMeta,
+    attrib_locations: &[(&str, u32)],
 ) -> Result<ShaderInternal, ShaderError> {
     unsafe {
         let vertex_shader = load_shader(GL_VERTEX_SHADER, vertex_shader)?;
-        let fragment_shader = load_shader(GL_FRAGMENT_SHADER, fragment_shader)?;
+        let fragment_shader = match load_shader(GL_FRAGMENT_SHADER, fragment_shader) {
+            Ok(fragment_shader) => fragment_shader,
+            Err(err) => {
+                glDeleteShader(vertex_shader);
+                return Err(err);
+            }
+        };
 
         let program = glCreateProgram();
         glAttachShader(program, vertex_shader);
         glAttachShader(program, fragment_shader);
+        // only flagged for deletion, freed together with the program
+        glDeleteShader(vertex_shader);
+        glDeleteShader(fragment_shader);
+        for (name, location) in attrib_locations {
+            let cname = CString::new(*name)?;
+            glBindAttribLocation(program, *location, cname.as_ptr());
+        }
         glLinkProgram(program);
 
         let mut link_status = 0;
@@ -74,11 +93,10 @@ fn load_shader_internal(
             assert!(max_length >= 1);
             let error_message =
                 std::string::String::from_utf8_lossy(&error_message[0..max_length as usize - 1]);
+            glDeleteProgram(program);
             return Err(ShaderError::LinkError(error_message.to_string()));
         }
 
-        glUseProgram(program);
-
         #[rustfmt::skip]
         let images = meta.images.iter().map(|name| ShaderImage {
             gl_loc: get_uniform_location(program, name),
@@ -87,6 +105,7 @@ fn load_shader_internal(
         #[rustfmt::skip]
         let uniforms = meta.uniforms.uniforms.iter().scan(0, |offset, uniform| {
             let res = ShaderUniform {
+                name: uniform.name.clone(),
                 gl_loc: get_uniform_location(program, &uniform.name),
                 _offset: *offset,
                 _size: uniform.uniform_type.size(),
@@ -97,10 +116,32 @@ fn load_shader_internal(
             Some(res)
         }).collect();
 
+        let missing_locations: Vec<String> = meta
+            .images
+            .iter()
+            .chain(meta.uniforms.uniforms.iter().map(|uniform| &uniform.name))
+            .filter(|name| get_uniform_location(program, name).is_none())
+            .cloned()
+            .collect();
+        if !missing_locations.is_empty() {
+            log::warn!(
+                "Shader uniforms not found in the program, they will be ignored: {:?}",
+                missing_locations
+            );
+        }
+
         Ok(ShaderInternal {
             program,
             images,
             uniforms,
+            missing_locations,
+            instance_id: get_uniform_location(program, "instance_id"),
+            wireframe_color: get_uniform_location(program, "wireframe_color"),
+            pipelines: 0,
+            attrib_locations: attrib_locations
+                .iter()
+                .map(|(name, location)| (name.to_string(), *location))
+                .collect(),
         })
     }
 }
@@ -160,9 +201,101 @@ impl Shader {
         fragment_shader: &str,
         meta: ShaderMeta,
     ) -> Result<Shader, ShaderError> {
-        let shader = load_shader_internal(vertex_shader, fragment_shader, meta)?;
-        ctx.shaders.push(shader);
-        Ok(Shader(ctx.shaders.len() - 1))
+        Self::with_attrib_locations(ctx, vertex_shader, fragment_shader, meta, &[])
+    }
+
+    /// Like `new`, but binds the attribute names to the given locations with
+    /// `glBindAttribLocation` before linking, so pipeline layouts don't depend
+    /// on how the driver assigns locations.
+    /// ```ignore
+    /// let shader = Shader::with_attrib_locations(ctx, VERTEX, FRAGMENT, meta, &[("pos", 0), ("uv", 1)])?;
+    /// ```
+    pub fn with_attrib_locations(
+        ctx: &mut GraphicsContext,
+        vertex_shader: &str,
+        fragment_shader: &str,
+        meta: ShaderMeta,
+        attrib_locations: &[(&str, u32)],
+    ) -> Result<Shader, ShaderError> {
+        let shader = load_shader_internal(vertex_shader, fragment_shader, meta, attrib_locations)?;
+        Ok(Shader(ctx.shaders.insert(shader)))
+    }
+
+    /// Uniform and image names of the shader meta that have no location in the program.
+    /// Setting them is silently ignored by `apply_uniforms` and `apply_bindings`.
+    pub fn missing_locations<'a>(&self, ctx: &'a GraphicsContext) -> &'a [String] {
+        &ctx.shaders[self.0].missing_locations
+    }
+
+    /// Fails with `ShaderError::MissingLocations` when any name of the shader meta
+    /// was not found in the program, e.g. to catch typos right after `Shader::new`.
+    pub fn check_locations(&self, ctx: &GraphicsContext) -> Result<(), ShaderError> {
+        match self.missing_locations(ctx) {
+            [] => Ok(()),
+            missing => Err(ShaderError::MissingLocations(missing.to_vec())),
+        }
+    }
+
+    /// Compile and link new sources for this shader, e.g. for live shader editing.
+    /// The handle and all pipelines created with it stay valid, their layouts are rebuilt
+    /// against the new program.
+    ///
+    /// Attribute locations given to `with_attrib_locations` are kept.
+    /// On a compile or link error the current program is kept and the error is returned.
+    pub fn replace_source(
+        &self,
+        ctx: &mut GraphicsContext,
+        vertex_shader: &str,
+        fragment_shader: &str,
+        meta: ShaderMeta,
+    ) -> Result<(), ShaderError> {
+        let attrib_locations: Vec<(&str, u32)> = ctx.shaders[self.0]
+            .attrib_locations
+            .iter()
+            .map(|(name, location)| (name.as_str(), *location))
+            .collect();
+        let mut shader =
+            load_shader_internal(vertex_shader, fragment_shader, meta, &attrib_locations)?;
+        let old = &mut ctx.shaders[self.0];
+        shader.pipelines = old.pipelines;
+        let old = std::mem::replace(old, shader);
+        let program = ctx.shaders[self.0].program;
+        unsafe { glDeleteProgram(old.program) };
+
+        for pipeline in ctx.pipelines.iter_mut() {
+            if pipeline.shader.0 == self.0 {
+                pipeline.layout =
+                    vertex_layout(program, &pipeline.buffer_layout, &pipeline.attributes);
+            }
+        }
+
+        // keep the applied pipeline drawing with a live program
+        if ctx.cache.program == old.program {
+            ctx.cache.use_program(program);
+        }
+        Ok(())
+    }
+
+    /// Delete the GPU program and free the shader slot, using the shader afterwards panics.
+    /// Fails with `ShaderError::InUse` while any pipeline created with this shader is alive,
+    /// see `Pipeline::delete`.
+    pub fn delete(&self, ctx: &mut GraphicsContext) -> Result<(), ShaderError> {
+        let pipelines = ctx
+            .shaders
+            .get(self.0)
+            .expect("Shader is already deleted")
+            .pipelines;
+        if pipelines != 0 {
+            return Err(ShaderError::InUse(pipelines));
+        }
+
+        let shader = ctx.shaders.remove(self.0).unwrap();
+        unsafe { glDeleteProgram(shader.program) };
+        // the name may be reused by the next program
+        if ctx.cache.program == shader.program {
+            ctx.cache.program = 0;
+        }
+        Ok(())
     }
 }
 
@@ -175,6 +308,7 @@ pub struct ShaderImage {
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct ShaderUniform {
+    pub(crate) name: String,
     pub(crate) gl_loc: UniformLocation,
     pub(crate) _offset: usize,
     pub(crate) _size: usize,
@@ -186,4 +320,14 @@ pub(crate) struct ShaderInternal {
     pub(crate) program: GLuint,
     pub(crate) images: Vec<ShaderImage>,
     pub(crate) uniforms: Vec<ShaderUniform>,
+    /// `instance_id` uniform set by the non-instanced `draw` fallback
+    pub(crate) instance_id: UniformLocation,
+    /// `wireframe_color` uniform set by `PipelineConf::wireframe_overlay` draws
+    pub(crate) wireframe_color: UniformLocation,
+    /// Number of pipelines using this shader
+    pub(crate) pipelines: usize,
+    /// Names of `ShaderMeta` without a location in `program`
+    pub(crate) missing_locations: Vec<String>,
+    /// Locations of `Shader::with_attrib_locations`, bound again by `replace_source`
+    pub(crate) attrib_locations: Vec<(String, u32)>,
 }
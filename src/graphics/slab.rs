@@ -0,0 +1,161 @@
+use std::ops::{Index, IndexMut};
+
+/// Position of a value in a [`Slab`].
+/// `generation` changes every time the slot is freed, so ids of deleted values never match again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct SlotId {
+    pub(crate) index: usize,
+    pub(crate) generation: u32,
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Storage for GPU resources addressed by handles.
+/// Slots of removed values are reused, stale handles are detected by their generation.
+pub(crate) struct Slab<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+}
+
+impl<T> Slab<T> {
+    pub(crate) fn new() -> Slab<T> {
+        Slab {
+            slots: vec![],
+            free: vec![],
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: T) -> SlotId {
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            slot.value = Some(value);
+            return SlotId {
+                index,
+                generation: slot.generation,
+            };
+        }
+
+        self.slots.push(Slot {
+            generation: 0,
+            value: Some(value),
+        });
+        SlotId {
+            index: self.slots.len() - 1,
+            generation: 0,
+        }
+    }
+
+    /// Take the value out of its slot, `None` when `id` is stale.
+    pub(crate) fn remove(&mut self, id: SlotId) -> Option<T> {
+        let slot = self.slots.get_mut(id.index)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        Some(value)
+    }
+
+    /// All values in the slab
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    pub(crate) fn get(&self, id: SlotId) -> Option<&T> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub(crate) fn get_mut(&mut self, id: SlotId) -> Option<&mut T> {
+        self.slots
+            .get_mut(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+}
+
+impl<T> Index<SlotId> for Slab<T> {
+    type Output = T;
+
+    fn index(&self, id: SlotId) -> &T {
+        self.get(id).unwrap_or_else(|| stale_handle::<T>(id))
+    }
+}
+
+impl<T> IndexMut<SlotId> for Slab<T> {
+    fn index_mut(&mut self, id: SlotId) -> &mut T {
+        self.get_mut(id).unwrap_or_else(|| stale_handle::<T>(id))
+    }
+}
+
+fn stale_handle<T>(id: SlotId) -> ! {
+    panic!(
+        "Use of a deleted {} handle (slot {}, generation {})",
+        std::any::type_name::<T>(),
+        id.index,
+        id.generation
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_slots_with_a_new_generation() {
+        let mut slab = Slab::new();
+        let a = slab.insert("a");
+        assert_eq!(slab.remove(a), Some("a"));
+
+        let b = slab.insert("b");
+        assert_eq!(b.index, a.index);
+        assert_ne!(b.generation, a.generation);
+        assert_eq!(slab[b], "b");
+    }
+
+    #[test]
+    fn rejects_stale_ids() {
+        let mut slab = Slab::new();
+        let a = slab.insert(1);
+        slab.remove(a);
+        let b = slab.insert(2);
+
+        assert_eq!(slab.get(a), None);
+        assert_eq!(slab.get_mut(a), None);
+        assert_eq!(slab.remove(a), None);
+        assert_eq!(slab.get(b), Some(&2));
+    }
+
+    #[test]
+    fn iterates_live_values_only() {
+        let mut slab = Slab::new();
+        let a = slab.insert(1);
+        slab.insert(2);
+        slab.insert(3);
+        slab.remove(a);
+
+        for value in slab.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(slab.iter().copied().collect::<Vec<_>>(), vec![20, 30]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Use of a deleted")]
+    fn indexing_a_stale_id_panics() {
+        let mut slab = Slab::new();
+        let a = slab.insert(1);
+        slab.remove(a);
+        let _ = slab[a];
+    }
+}
@@ -0,0 +1,191 @@
+use super::*;
+
+const SPRITE_VERTEX: &str = r#"
+attribute vec2 pos;
+attribute vec2 uv0;
+attribute vec4 color0;
+uniform vec2 screen_size;
+varying vec2 uv;
+varying vec4 color;
+void main() {
+    vec2 ndc = pos / screen_size * 2.0 - 1.0;
+    uv = uv0;
+    color = color0;
+    gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);
+}
+"#;
+
+const SPRITE_FRAGMENT: &str = r#"
+#ifdef GL_ES
+precision mediump float;
+#endif
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D tex;
+void main() {
+    gl_FragColor = texture2D(tex, uv) * color;
+}
+"#;
+
+/// Quads drawn before `flush` is forced
+const MAX_QUADS: usize = 4096;
+
+/// Axis aligned rectangle, in pixels for destinations and 0..1 for texture coordinates
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub const fn new(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    /// Texture coordinates of the whole texture
+    pub const UNIT: Rect = Rect::new(0., 0., 1., 1.);
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SpriteVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+    color: [f32; 4],
+}
+
+/// Immediate mode textured quads with a built-in shader, one draw call per run of quads
+/// sharing a texture.
+/// ```ignore
+/// let mut batch = SpriteBatch::new(ctx);
+///
+/// ctx.begin_pass(None, PassAction::Nothing);
+/// batch.draw_quad(ctx, &texture, Rect::new(10., 10., 64., 64.), Rect::UNIT, Color::WHITE);
+/// batch.flush(ctx);
+/// ctx.end_render_pass();
+/// ```
+/// Destinations are in pixels of the current viewport, origin at the top left.
+/// Applies its own pipeline, so the previously applied pipeline has to be applied again.
+pub struct SpriteBatch {
+    shader: Shader,
+    pipeline: Pipeline,
+    bindings: Bindings,
+    vertices: Vec<SpriteVertex>,
+    /// Texture of the pending quads, kept alive until they are flushed
+    texture: Option<Texture>,
+}
+
+impl SpriteBatch {
+    pub fn new(ctx: &mut GraphicsContext) -> SpriteBatch {
+        let meta = ShaderMeta {
+            uniforms: UniformBlockLayout {
+                uniforms: vec![UniformDesc::new("screen_size", UniformType::Float2)],
+            },
+            images: vec!["tex".to_string()],
+        };
+        let shader = Shader::new(ctx, SPRITE_VERTEX, SPRITE_FRAGMENT, meta)
+            .expect("Built-in sprite shader failed to compile");
+        let pipeline = Pipeline::with_params(
+            ctx,
+            &[BufferLayout::default()],
+            &[
+                VertexAttribute::new("pos", VertexFormat::Float2),
+                VertexAttribute::new("uv0", VertexFormat::Float2),
+                VertexAttribute::new("color0", VertexFormat::Float4),
+            ],
+            shader,
+            PipelineConf {
+                color_blend: Some(BlendState::new(
+                    Equation::Add,
+                    BlendFactor::Value(BlendValue::SourceAlpha),
+                    BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                )),
+                ..Default::default()
+            },
+        );
+
+        let vertex_buffer = Buffer::stream(
+            ctx,
+            BufferType::VertexBuffer,
+            None,
+            MAX_QUADS * 4 * mem::size_of::<SpriteVertex>(),
+        );
+        let bindings = Bindings {
+            vertex_buffers: vec![vertex_buffer],
+            vertex_buffer_offsets: vec![],
+            index_buffer: Buffer::quad_indices(ctx, MAX_QUADS),
+            // textures change between flushes and are bound directly
+            images: vec![Texture::empty()],
+        };
+
+        SpriteBatch {
+            shader,
+            pipeline,
+            bindings,
+            vertices: Vec::with_capacity(MAX_QUADS * 4),
+            texture: None,
+        }
+    }
+
+    /// Queue `src_uv` of `texture` stretched over `dst_rect`, tinted by `color`.
+    /// Pending quads are flushed first when the texture changes or the batch is full.
+    pub fn draw_quad(
+        &mut self,
+        ctx: &mut GraphicsContext,
+        texture: &Texture,
+        dst_rect: Rect,
+        src_uv: Rect,
+        color: Color,
+    ) {
+        if self.texture.as_ref() != Some(texture) || self.vertices.len() == MAX_QUADS * 4 {
+            self.flush(ctx);
+            self.texture = Some(texture.clone());
+        }
+
+        let Rect { x, y, w, h } = dst_rect;
+        let color = [color.r, color.g, color.b, color.a];
+        let corners = [(0., 0.), (1., 0.), (1., 1.), (0., 1.)];
+        self.vertices
+            .extend(corners.iter().map(|&(cx, cy)| SpriteVertex {
+                pos: [x + w * cx, y + h * cy],
+                uv: [src_uv.x + src_uv.w * cx, src_uv.y + src_uv.h * cy],
+                color,
+            }));
+    }
+
+    /// Draw all pending quads. Should be called before `end_render_pass`.
+    pub fn flush(&mut self, ctx: &mut GraphicsContext) {
+        let texture = match self.texture.take() {
+            Some(texture) if !self.vertices.is_empty() => texture,
+            _ => return,
+        };
+
+        // the viewport is unknown to the cache only after `set_viewports`
+        let (_, _, w, h) = ctx
+            .cache
+            .viewport
+            .or_else(|| ctx.pass_stack.last().map(|&(_, w, h)| (0, 0, w, h)))
+            .expect("SpriteBatch::flush called outside of a pass");
+        let screen_size = [w as f32, h as f32];
+
+        // the previous flush may still be reading the buffer
+        self.bindings.vertex_buffers[0].orphan_and_update(ctx, &self.vertices);
+        ctx.apply_pipeline(&self.pipeline);
+        ctx.apply_bindings(&self.bindings);
+        ctx.cache.bind_texture(0, texture.texture);
+        ctx.apply_uniforms(&screen_size);
+        ctx.draw(0, (self.vertices.len() / 4 * 6) as i32, 1);
+
+        self.vertices.clear();
+    }
+
+    /// Delete the built-in pipeline and shader, pending quads are discarded.
+    pub fn delete(self, ctx: &mut GraphicsContext) {
+        self.pipeline.delete(ctx);
+        self.shader
+            .delete(ctx)
+            .expect("Sprite shader is used only by its pipeline");
+    }
+}
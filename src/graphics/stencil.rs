@@ -4,6 +4,14 @@ pub struct StencilState {
     pub back: StencilFaceState,
 }
 
+/// Polygon faces a stencil setting applies to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StencilFace {
+    Front,
+    Back,
+    FrontAndBack,
+}
+
 /// Depth and stencil compare function
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CompareFunc {
@@ -1,9 +1,13 @@
+use super::deletion_queue::{GlHandle, GlObject};
 use super::gl::{self, *};
-use super::GraphicsContext;
+use super::{Buffer, BufferType, Features, GraphicsContext};
+use std::rc::Rc;
 
+/// Clones share the GL texture, it is deleted once the last clone is dropped.
 #[derive(Clone, Debug, PartialEq, Hash)]
 pub struct Texture {
     pub(crate) texture: GLuint,
+    _handle: Rc<GlHandle>,
     pub width: u32,
     pub height: u32,
     pub format: TextureFormat,
@@ -13,6 +17,7 @@ impl Texture {
     pub fn empty() -> Texture {
         Texture {
             texture: 0,
+            _handle: Rc::new(GlHandle(GlObject::Texture(0))),
             width: 0,
             height: 0,
             format: TextureFormat::RGBA8,
@@ -26,6 +31,7 @@ impl Texture {
     pub unsafe fn from_raw_id(texture: GLuint) -> Self {
         Self {
             texture,
+            _handle: Rc::new(GlHandle(GlObject::Texture(texture))),
             width: 0,
             height: 0,
             format: TextureFormat::RGBA8, // assumed for now
@@ -33,14 +39,6 @@ impl Texture {
     }
 }
 
-impl Drop for Texture {
-    fn drop(&mut self) {
-        unsafe {
-            glDeleteTextures(1, &self.texture as *const _);
-        }
-    }
-}
-
 /// List of all the possible formats of input data when uploading to texture.
 /// The list is built by intersection of texture formats supported by 3.3 core profile and webgl1.
 #[repr(u8)]
@@ -50,6 +48,16 @@ pub enum TextureFormat {
     RGBA8,
     Depth,
     Alpha,
+    /// S3TC/BC1 compressed RGBA, 8 bytes per 4x4 block
+    DXT1,
+    /// S3TC/BC3 compressed RGBA, 16 bytes per 4x4 block
+    DXT5,
+    /// ETC2 compressed RGB8, 8 bytes per 4x4 block
+    ETC2,
+    /// Unsigned integer RGBA8, sampled with `usampler2D`
+    RGBA8UI,
+    /// Unsigned integer 32 bit red channel, sampled with `usampler2D`
+    R32UI,
 }
 
 /// Converts from TextureFormat to (internal_format, format, pixel_type)
@@ -63,6 +71,11 @@ impl From<TextureFormat> for (GLenum, GLenum, GLenum) {
             TextureFormat::Alpha => (GL_ALPHA, GL_ALPHA, GL_UNSIGNED_BYTE),
             #[cfg(not(target_arch = "wasm32"))]
             TextureFormat::Alpha => (GL_R8, GL_RED, GL_UNSIGNED_BYTE), // texture updates will swizzle Red -> Alpha to match WASM
+            TextureFormat::DXT1 => (GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, GL_RGBA, GL_UNSIGNED_BYTE),
+            TextureFormat::DXT5 => (GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, GL_RGBA, GL_UNSIGNED_BYTE),
+            TextureFormat::ETC2 => (GL_COMPRESSED_RGB8_ETC2, GL_RGB, GL_UNSIGNED_BYTE),
+            TextureFormat::RGBA8UI => (GL_RGBA8UI, GL_RGBA_INTEGER, GL_UNSIGNED_BYTE),
+            TextureFormat::R32UI => (GL_R32UI, GL_RED_INTEGER, GL_UNSIGNED_INT),
         }
     }
 }
@@ -70,13 +83,44 @@ impl TextureFormat {
     /// Returns the size in bytes of texture with `dimensions`.
     pub fn size(self, width: u32, height: u32) -> u32 {
         let square = width * height;
+        let blocks = width.div_ceil(4) * height.div_ceil(4);
         match self {
             TextureFormat::RGB8 => 3 * square,
-            TextureFormat::RGBA8 => 4 * square,
+            TextureFormat::RGBA8 | TextureFormat::RGBA8UI | TextureFormat::R32UI => 4 * square,
             TextureFormat::Depth => 2 * square,
             TextureFormat::Alpha => 1 * square,
+            TextureFormat::DXT1 | TextureFormat::ETC2 => 8 * blocks,
+            TextureFormat::DXT5 => 16 * blocks,
         }
     }
+
+    /// Sized internal format for `glRenderbufferStorage*`
+    pub(crate) fn renderbuffer_format(self) -> GLenum {
+        match self {
+            TextureFormat::RGB8 => GL_RGB8,
+            TextureFormat::RGBA8 => GL_RGBA8,
+            TextureFormat::Depth => GL_DEPTH_COMPONENT16,
+            TextureFormat::Alpha => GL_R8,
+            TextureFormat::RGBA8UI => GL_RGBA8UI,
+            TextureFormat::R32UI => GL_R32UI,
+            TextureFormat::DXT1 | TextureFormat::DXT5 | TextureFormat::ETC2 => {
+                panic!("Compressed formats can't be rendered to")
+            }
+        }
+    }
+
+    /// Block compressed formats, uploaded with `glCompressedTexImage2D`
+    pub fn is_compressed(self) -> bool {
+        matches!(
+            self,
+            TextureFormat::DXT1 | TextureFormat::DXT5 | TextureFormat::ETC2
+        )
+    }
+
+    /// Integer formats, uploaded with `*_INTEGER` pixel formats and never filtered
+    pub fn is_integer(self) -> bool {
+        matches!(self, TextureFormat::RGBA8UI | TextureFormat::R32UI)
+    }
 }
 
 impl Default for TextureParams {
@@ -87,6 +131,8 @@ impl Default for TextureParams {
             filter: FilterMode::Linear,
             width: 0,
             height: 0,
+            mipmap: false,
+            border_color: [0.; 4],
         }
     }
 }
@@ -100,6 +146,21 @@ pub enum TextureWrap {
     Mirror = GL_MIRRORED_REPEAT as isize,
     /// Samples at coord x + 1 map to coord 1.
     Clamp = GL_CLAMP_TO_EDGE as isize,
+    /// Samples outside of [0, 1] return the border color, see `TextureParams::border_color`
+    /// and [`Texture::set_border_color`].
+    ///
+    /// NOTE: falls back to `Clamp` when `features.clamp_to_border` is false.
+    ClampToBorder = GL_CLAMP_TO_BORDER as isize,
+}
+
+impl TextureWrap {
+    pub(crate) fn gl_wrap(self, features: &Features) -> i32 {
+        if self == TextureWrap::ClampToBorder && !features.clamp_to_border {
+            log::warn!("Texture border clamp is not supported by the GPU, using Clamp instead");
+            return TextureWrap::Clamp as i32;
+        }
+        self as i32
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
@@ -108,6 +169,16 @@ pub enum FilterMode {
     Nearest = GL_NEAREST as isize,
 }
 
+impl FilterMode {
+    /// Min filter using mip levels, trilinear for `Linear`
+    fn mipmapped(self) -> GLenum {
+        match self {
+            FilterMode::Linear => GL_LINEAR_MIPMAP_LINEAR,
+            FilterMode::Nearest => GL_NEAREST_MIPMAP_NEAREST,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum TextureAccess {
     /// Used as read-only from GPU
@@ -123,6 +194,11 @@ pub struct TextureParams {
     pub filter: FilterMode,
     pub width: u32,
     pub height: u32,
+    /// Minify with a mipmapped `filter`, mips of the initial data are generated at creation.
+    /// Textures without initial data need `Texture::generate_mipmaps` before sampling.
+    pub mipmap: bool,
+    /// Color sampled outside of a `TextureWrap::ClampToBorder` texture, transparent black by default
+    pub border_color: [f32; 4],
 }
 
 impl Texture {
@@ -144,6 +220,22 @@ impl Texture {
             );
         }
 
+        assert!(
+            ctx.features.is_format_supported(params.format),
+            "{:?} textures are not supported by the GPU",
+            params.format
+        );
+        assert!(
+            !params.format.is_integer() || params.filter == FilterMode::Nearest,
+            "{:?} textures can only use FilterMode::Nearest",
+            params.format
+        );
+        assert!(
+            !params.mipmap || !params.format.is_compressed(),
+            "Mipmaps of {:?} textures can't be generated, use Texture::with_mips",
+            params.format
+        );
+
         let (internal_format, format, pixel_type) = params.format.into();
 
         ctx.cache.store_texture_binding(0);
@@ -155,24 +247,57 @@ impl Texture {
             ctx.cache.bind_texture(0, texture);
             glPixelStorei(GL_UNPACK_ALIGNMENT, 1); // miniquad always uses row alignment of 1
 
-            glTexImage2D(
-                GL_TEXTURE_2D,
-                0,
-                internal_format as i32,
-                params.width as i32,
-                params.height as i32,
-                0,
-                format,
-                pixel_type,
-                match bytes {
-                    Some(bytes) => bytes.as_ptr() as *const _,
-                    Option::None => std::ptr::null(),
-                },
-            );
+            let data = match bytes {
+                Some(bytes) => bytes.as_ptr() as *const _,
+                Option::None => std::ptr::null(),
+            };
+            if params.format.is_compressed() {
+                glCompressedTexImage2D(
+                    GL_TEXTURE_2D,
+                    0,
+                    internal_format,
+                    params.width as i32,
+                    params.height as i32,
+                    0,
+                    params.format.size(params.width, params.height) as i32,
+                    data,
+                );
+            } else {
+                glTexImage2D(
+                    GL_TEXTURE_2D,
+                    0,
+                    internal_format as i32,
+                    params.width as i32,
+                    params.height as i32,
+                    0,
+                    format,
+                    pixel_type,
+                    data,
+                );
+            }
 
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, params.wrap as i32);
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, params.wrap as i32);
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, params.filter as i32);
+            let wrap = params.wrap.gl_wrap(&ctx.features);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, wrap);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, wrap);
+            if wrap == GL_CLAMP_TO_BORDER as i32 {
+                glTexParameterfv(
+                    GL_TEXTURE_2D,
+                    GL_TEXTURE_BORDER_COLOR,
+                    params.border_color.as_ptr(),
+                );
+            }
+            if params.mipmap {
+                glTexParameteri(
+                    GL_TEXTURE_2D,
+                    GL_TEXTURE_MIN_FILTER,
+                    params.filter.mipmapped() as i32,
+                );
+                if bytes.is_some() {
+                    glGenerateMipmap(GL_TEXTURE_2D);
+                }
+            } else {
+                glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, params.filter as i32);
+            }
             glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, params.filter as i32);
 
             if cfg!(not(target_arch = "wasm32")) {
@@ -191,6 +316,7 @@ impl Texture {
 
         Texture {
             texture,
+            _handle: Rc::new(GlHandle(GlObject::Texture(texture))),
             width: params.width,
             height: params.height,
             format: params.format,
@@ -208,7 +334,13 @@ impl Texture {
 
     /// Upload RGBA8 texture to GPU
     pub fn from_rgba8(ctx: &mut GraphicsContext, width: u16, height: u16, bytes: &[u8]) -> Texture {
-        assert_eq!(width as usize * height as usize * 4, bytes.len());
+        assert_eq!(
+            width as usize * height as usize * 4,
+            bytes.len(),
+            "{}x{} RGBA8 texture needs width * height * 4 bytes",
+            width,
+            height
+        );
 
         Self::from_data_and_format(
             ctx,
@@ -219,10 +351,120 @@ impl Texture {
                 format: TextureFormat::RGBA8,
                 wrap: TextureWrap::Clamp,
                 filter: FilterMode::Linear,
+                mipmap: false,
+                border_color: [0.; 4],
             },
         )
     }
 
+    /// Upload a texture with a precomputed mip chain, `levels[0]` being the full size image
+    /// and every following level half the size of the previous one (rounded down, at least 1).
+    ///
+    /// `params.mipmap` is implied by more than one level, the min filter is the mipmapped
+    /// form of `params.filter` like for `TextureParams::mipmap`, the mips are never generated.
+    pub fn with_mips(
+        ctx: &mut GraphicsContext,
+        params: TextureParams,
+        levels: &[&[u8]],
+    ) -> Texture {
+        let TextureParams {
+            width,
+            height,
+            format,
+            ..
+        } = params;
+        let max_levels = 32 - width.max(height).max(1).leading_zeros() as usize;
+        assert!(
+            !levels.is_empty() && levels.len() <= max_levels,
+            "{}x{} texture can have 1 to {} mip levels, got {}",
+            width,
+            height,
+            max_levels,
+            levels.len()
+        );
+
+        let texture = Self::from_data_and_format(
+            ctx,
+            levels[0],
+            TextureParams {
+                mipmap: false,
+                ..params
+            },
+        );
+
+        let (internal_format, gl_format, pixel_type) = format.into();
+
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, texture.texture);
+        unsafe {
+            glPixelStorei(GL_UNPACK_ALIGNMENT, 1); // miniquad always uses row alignment of 1
+
+            for (level, bytes) in levels.iter().enumerate().skip(1) {
+                let level_width = (width >> level).max(1);
+                let level_height = (height >> level).max(1);
+                assert_eq!(
+                    format.size(level_width, level_height) as usize,
+                    bytes.len(),
+                    "mip level {} size mismatch",
+                    level
+                );
+
+                if format.is_compressed() {
+                    glCompressedTexImage2D(
+                        GL_TEXTURE_2D,
+                        level as i32,
+                        internal_format,
+                        level_width as i32,
+                        level_height as i32,
+                        0,
+                        bytes.len() as i32,
+                        bytes.as_ptr() as *const _,
+                    );
+                } else {
+                    glTexImage2D(
+                        GL_TEXTURE_2D,
+                        level as i32,
+                        internal_format as i32,
+                        level_width as i32,
+                        level_height as i32,
+                        0,
+                        gl_format,
+                        pixel_type,
+                        bytes.as_ptr() as *const _,
+                    );
+                }
+            }
+
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAX_LEVEL, levels.len() as i32 - 1);
+            if levels.len() > 1 {
+                glTexParameteri(
+                    GL_TEXTURE_2D,
+                    GL_TEXTURE_MIN_FILTER,
+                    params.filter.mipmapped() as i32,
+                );
+            }
+        }
+        ctx.cache.restore_texture_binding(0);
+
+        texture
+    }
+
+    /// Build the mip chain from the base level, e.g. after `update` or rendering to the texture.
+    /// The texture size and base level content have to be set before calling.
+    ///
+    /// Sampling uses the mips only with a mipmapped min filter, see `TextureParams::mipmap`.
+    pub fn generate_mipmaps(&self, ctx: &mut GraphicsContext) {
+        assert!(
+            !self.format.is_compressed(),
+            "Mipmaps of {:?} textures can't be generated, use Texture::with_mips",
+            self.format
+        );
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+        unsafe { glGenerateMipmap(GL_TEXTURE_2D) };
+        ctx.cache.restore_texture_binding(0);
+    }
+
     /// Set the min and mag filter to `filter`
     pub fn set_filter(&self, ctx: &mut GraphicsContext, filter: FilterMode) {
         ctx.cache.store_texture_binding(0);
@@ -255,8 +497,9 @@ impl Texture {
         ctx.cache.store_texture_binding(0);
         ctx.cache.bind_texture(0, self.texture);
         unsafe {
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, wrap as i32);
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, wrap as i32);
+            let wrap = wrap.gl_wrap(&ctx.features);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, wrap);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, wrap);
         }
         ctx.cache.restore_texture_binding(0);
     }
@@ -266,8 +509,33 @@ impl Texture {
         ctx.cache.store_texture_binding(0);
         ctx.cache.bind_texture(0, self.texture);
         unsafe {
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, x_wrap as i32);
-            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, y_wrap as i32);
+            glTexParameteri(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_WRAP_S,
+                x_wrap.gl_wrap(&ctx.features),
+            );
+            glTexParameteri(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_WRAP_T,
+                y_wrap.gl_wrap(&ctx.features),
+            );
+        }
+        ctx.cache.restore_texture_binding(0);
+    }
+
+    /// Set the color returned when sampling outside of a `TextureWrap::ClampToBorder` texture.
+    ///
+    /// NOTE: ignored when `features.clamp_to_border` is false.
+    pub fn set_border_color(&self, ctx: &mut GraphicsContext, color: [f32; 4]) {
+        if !ctx.features.clamp_to_border {
+            log::warn!("Texture border color is not supported by the GPU");
+            return;
+        }
+
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+        unsafe {
+            glTexParameterfv(GL_TEXTURE_2D, GL_TEXTURE_BORDER_COLOR, color.as_ptr());
         }
         ctx.cache.restore_texture_binding(0);
     }
@@ -309,22 +577,16 @@ impl Texture {
         ctx.cache.restore_texture_binding(0);
     }
 
-    /// Update whole texture content
-    /// bytes should be width * height * 4 size - non rgba8 textures are not supported yet anyway
+    /// Replace the whole texture content in place, e.g. with a new video frame.
+    /// `bytes` are tightly packed rows in the texture format.
     pub fn update(&self, ctx: &mut GraphicsContext, bytes: &[u8]) {
-        assert_eq!(self.size(self.width, self.height), bytes.len());
-
-        self.update_texture_part(
-            ctx,
-            0 as _,
-            0 as _,
-            self.width as _,
-            self.height as _,
-            bytes,
-        )
+        self.update_part(ctx, 0, 0, self.width as _, self.height as _, bytes)
     }
 
-    pub fn update_texture_part(
+    /// Replace the `width`x`height` rectangle at `x_offset, y_offset` with `bytes`
+    /// through `glTexSubImage2D`. Panics when `bytes` don't match the rectangle size
+    /// or the rectangle is out of the texture.
+    pub fn update_part(
         &self,
         ctx: &mut GraphicsContext,
         x_offset: i32,
@@ -333,9 +595,34 @@ impl Texture {
         height: i32,
         bytes: &[u8],
     ) {
-        assert_eq!(self.size(width as _, height as _), bytes.len());
-        assert!(x_offset + width <= self.width as _);
-        assert!(y_offset + height <= self.height as _);
+        assert!(
+            !self.format.is_compressed(),
+            "Use update_compressed_part for compressed textures"
+        );
+        assert!(
+            x_offset >= 0
+                && y_offset >= 0
+                && width >= 0
+                && height >= 0
+                && x_offset + width <= self.width as _
+                && y_offset + height <= self.height as _,
+            "Rectangle {}x{} at ({}, {}) is out of the {}x{} texture",
+            width,
+            height,
+            x_offset,
+            y_offset,
+            self.width,
+            self.height
+        );
+        assert_eq!(
+            self.size(width as _, height as _),
+            bytes.len(),
+            "{}x{} {:?} rectangle needs {} bytes",
+            width,
+            height,
+            self.format,
+            self.size(width as _, height as _)
+        );
 
         ctx.cache.store_texture_binding(0);
         ctx.cache.bind_texture(0, self.texture);
@@ -361,17 +648,131 @@ impl Texture {
         ctx.cache.restore_texture_binding(0);
     }
 
-    /// Read texture data into CPU memory
-    pub fn read_pixels(&self, bytes: &mut [u8]) {
+    #[deprecated(note = "renamed to `update_part`")]
+    pub fn update_texture_part(
+        &self,
+        ctx: &mut GraphicsContext,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        bytes: &[u8],
+    ) {
+        self.update_part(ctx, x_offset, y_offset, width, height, bytes)
+    }
+
+    /// Replace the whole texture with the pixels at byte `offset` of a `BufferType::PixelUnpack`
+    /// buffer. The copy happens on the GPU, so the upload overlaps with rendering instead of
+    /// stalling on CPU memory.
+    ///
+    /// NOTE: `features.pixel_buffer` check is required.
+    pub fn update_from_pbo(&self, ctx: &mut GraphicsContext, pbo: &Buffer, offset: usize) {
+        assert!(
+            ctx.features.pixel_buffer,
+            "Pixel buffers are not supported by the GPU"
+        );
+        assert!(
+            pbo.buffer_type == BufferType::PixelUnpack,
+            "update_from_pbo reads from BufferType::PixelUnpack buffers"
+        );
+        assert!(
+            !self.format.is_compressed(),
+            "Use update_compressed_part for compressed textures"
+        );
+        assert!(offset + self.size(self.width, self.height) <= pbo.size());
+
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+        ctx.cache
+            .bind_buffer(GL_PIXEL_UNPACK_BUFFER, pbo.gl_buf, None);
+
+        let (_, format, pixel_type) = self.format.into();
+
+        unsafe {
+            glPixelStorei(GL_UNPACK_ALIGNMENT, 1); // miniquad always uses row alignment of 1
+
+            glTexSubImage2D(
+                GL_TEXTURE_2D,
+                0,
+                0,
+                0,
+                self.width as _,
+                self.height as _,
+                format,
+                pixel_type,
+                offset as *const _,
+            );
+        }
+
+        ctx.cache.restore_buffer_binding(GL_PIXEL_UNPACK_BUFFER);
+        ctx.cache.restore_texture_binding(0);
+    }
+
+    /// Replace a part of a compressed texture with compressed `bytes`.
+    /// Offsets should be multiples of the 4x4 block size.
+    pub fn update_compressed_part(
+        &self,
+        ctx: &mut GraphicsContext,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        bytes: &[u8],
+    ) {
+        assert!(self.format.is_compressed());
+        assert_eq!(self.size(width as _, height as _), bytes.len());
+        assert!(x_offset + width <= self.width as _);
+        assert!(y_offset + height <= self.height as _);
+
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+
+        let (internal_format, _, _) = self.format.into();
+
+        unsafe {
+            glCompressedTexSubImage2D(
+                GL_TEXTURE_2D,
+                0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                internal_format,
+                bytes.len() as i32,
+                bytes.as_ptr() as *const _,
+            );
+        }
+
+        ctx.cache.restore_texture_binding(0);
+    }
+
+    /// Read texture data into CPU memory, e.g. the result of an offscreen pass.
+    /// `bytes` must hold exactly `width * height` pixels of the texture format.
+    /// `GL_PACK_ALIGNMENT` is restored afterwards.
+    pub fn read_pixels(&self, ctx: &mut GraphicsContext, bytes: &mut [u8]) {
         if self.format == TextureFormat::Alpha {
             unimplemented!("read_pixels is not implement for Alpha textures");
         }
+        assert!(
+            !self.format.is_compressed(),
+            "Compressed textures can't be read back"
+        );
+        assert_eq!(
+            self.size(self.width, self.height),
+            bytes.len(),
+            "{}x{} {:?} texture doesn't fit into {} bytes",
+            self.width,
+            self.height,
+            self.format,
+            bytes.len()
+        );
         let (_, format, pixel_type) = self.format.into();
 
         let mut fbo = 0;
         unsafe {
-            let mut binded_fbo: i32 = 0;
-            glGetIntegerv(gl::GL_DRAW_FRAMEBUFFER_BINDING, &mut binded_fbo);
+            let mut pack_alignment = 4;
+            glGetIntegerv(GL_PACK_ALIGNMENT, &mut pack_alignment);
+            glPixelStorei(GL_PACK_ALIGNMENT, 1);
             glGenFramebuffers(1, &mut fbo);
             glBindFramebuffer(gl::GL_FRAMEBUFFER, fbo);
             glFramebufferTexture2D(
@@ -391,12 +792,50 @@ impl Texture {
                 pixel_type,
                 bytes.as_mut_ptr() as _,
             );
+            glPixelStorei(GL_PACK_ALIGNMENT, pack_alignment);
 
-            glBindFramebuffer(gl::GL_FRAMEBUFFER, binded_fbo as _);
+            glBindFramebuffer(gl::GL_FRAMEBUFFER, ctx.cache.framebuffer);
             glDeleteFramebuffers(1, &fbo);
         }
     }
 
+    /// Download the whole texture with `glGetTexImage`, without attaching it to a framebuffer.
+    /// Works for formats `read_pixels` can't read, like `Depth`.
+    ///
+    /// `GL_PACK_ALIGNMENT` is restored afterwards.
+    ///
+    /// NOTE: `features.get_tex_image` check is required, GLES has no `glGetTexImage`.
+    pub fn download(&self, ctx: &mut GraphicsContext, out: &mut [u8]) {
+        assert!(
+            ctx.features.get_tex_image,
+            "glGetTexImage is not available on GLES"
+        );
+        assert!(
+            !self.format.is_compressed(),
+            "Compressed textures can't be downloaded"
+        );
+        assert_eq!(self.size(self.width, self.height), out.len());
+
+        let (_, format, pixel_type) = self.format.into();
+
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+        unsafe {
+            let mut pack_alignment = 4;
+            glGetIntegerv(GL_PACK_ALIGNMENT, &mut pack_alignment);
+            glPixelStorei(GL_PACK_ALIGNMENT, 1);
+            glGetTexImage(
+                GL_TEXTURE_2D,
+                0,
+                format,
+                pixel_type,
+                out.as_mut_ptr() as *mut _,
+            );
+            glPixelStorei(GL_PACK_ALIGNMENT, pack_alignment);
+        }
+        ctx.cache.restore_texture_binding(0);
+    }
+
     #[inline]
     fn size(&self, width: u32, height: u32) -> usize {
         self.format.size(width, height) as usize
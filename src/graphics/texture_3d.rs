@@ -0,0 +1,129 @@
+use super::*;
+
+/// Volume texture, sampled with `sampler3D` in shaders.
+///
+/// NOTE: `features.texture_3d` check is required.
+#[derive(Debug, PartialEq, Hash)]
+pub struct Texture3D {
+    pub(crate) texture: GLuint,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub format: TextureFormat,
+}
+
+impl Texture3D {
+    /// `bytes` hold `depth` slices of `params.width` x `params.height` pixels, one after another.
+    pub fn new(
+        ctx: &mut GraphicsContext,
+        bytes: Option<&[u8]>,
+        params: TextureParams,
+        depth: u32,
+    ) -> Texture3D {
+        assert!(
+            ctx.features.texture_3d,
+            "3D textures are not supported by the GPU"
+        );
+        assert!(
+            !params.format.is_compressed(),
+            "3D textures can't use compressed formats"
+        );
+        if let Some(bytes_data) = bytes {
+            assert_eq!(
+                (params.format.size(params.width, params.height) * depth) as usize,
+                bytes_data.len()
+            );
+        }
+
+        let (internal_format, format, pixel_type) = params.format.into();
+
+        ctx.cache.store_texture_3d_binding(0);
+
+        let mut texture: GLuint = 0;
+
+        unsafe {
+            glGenTextures(1, &mut texture as *mut _);
+            ctx.cache.bind_texture_3d(0, texture);
+            glPixelStorei(GL_UNPACK_ALIGNMENT, 1); // miniquad always uses row alignment of 1
+
+            glTexImage3D(
+                GL_TEXTURE_3D,
+                0,
+                internal_format as i32,
+                params.width as i32,
+                params.height as i32,
+                depth as i32,
+                0,
+                format,
+                pixel_type,
+                match bytes {
+                    Some(bytes) => bytes.as_ptr() as *const _,
+                    Option::None => std::ptr::null(),
+                },
+            );
+
+            let wrap = params.wrap.gl_wrap(&ctx.features);
+            glTexParameteri(GL_TEXTURE_3D, GL_TEXTURE_WRAP_S, wrap);
+            glTexParameteri(GL_TEXTURE_3D, GL_TEXTURE_WRAP_T, wrap);
+            glTexParameteri(GL_TEXTURE_3D, GL_TEXTURE_WRAP_R, wrap);
+            if wrap == GL_CLAMP_TO_BORDER as i32 {
+                glTexParameterfv(
+                    GL_TEXTURE_3D,
+                    GL_TEXTURE_BORDER_COLOR,
+                    params.border_color.as_ptr(),
+                );
+            }
+            glTexParameteri(GL_TEXTURE_3D, GL_TEXTURE_MIN_FILTER, params.filter as i32);
+            glTexParameteri(GL_TEXTURE_3D, GL_TEXTURE_MAG_FILTER, params.filter as i32);
+        }
+        ctx.cache.restore_texture_3d_binding(0);
+
+        Texture3D {
+            texture,
+            width: params.width,
+            height: params.height,
+            depth,
+            format: params.format,
+        }
+    }
+
+    /// Color grading lookup table of `size`x`size`x`size` RGB8 texels,
+    /// red varying fastest and blue slowest, sampled with linear filtering.
+    pub fn from_lut(ctx: &mut GraphicsContext, size: u16, rgb_data: &[u8]) -> Texture3D {
+        Self::new(
+            ctx,
+            Some(rgb_data),
+            TextureParams {
+                format: TextureFormat::RGB8,
+                wrap: TextureWrap::Clamp,
+                filter: FilterMode::Linear,
+                width: size as u32,
+                height: size as u32,
+                mipmap: false,
+                border_color: [0.; 4],
+            },
+            size as u32,
+        )
+    }
+
+    pub fn gl_internal_id(&self) -> GLuint {
+        self.texture
+    }
+}
+
+impl Drop for Texture3D {
+    fn drop(&mut self) {
+        delete_object(GlObject::Texture(self.texture));
+    }
+}
+
+impl GraphicsContext {
+    /// Bind `texture` to the texture unit of image `slot` of the current shader.
+    ///
+    /// The 3D target of a texture unit is independent of the 2D one, so call this after
+    /// [`GraphicsContext::apply_bindings()`] with any texture in `bindings.images[slot]`.
+    pub fn apply_texture_3d(&mut self, slot: usize, texture: &Texture3D) -> &mut Self {
+        self.cache.bind_texture_3d(slot, texture.texture);
+        self
+    }
+}
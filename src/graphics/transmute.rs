@@ -6,6 +6,8 @@ impl From<Equation> for GLenum {
             Equation::Add => GL_FUNC_ADD,
             Equation::Subtract => GL_FUNC_SUBTRACT,
             Equation::ReverseSubtract => GL_FUNC_REVERSE_SUBTRACT,
+            Equation::Min => GL_MIN,
+            Equation::Max => GL_MAX,
         }
     }
 }
@@ -19,10 +21,18 @@ impl From<BlendFactor> for GLenum {
             BlendFactor::Value(BlendValue::SourceAlpha) => GL_SRC_ALPHA,
             BlendFactor::Value(BlendValue::DestinationColor) => GL_DST_COLOR,
             BlendFactor::Value(BlendValue::DestinationAlpha) => GL_DST_ALPHA,
+            BlendFactor::Value(BlendValue::ConstantColor) => GL_CONSTANT_COLOR,
+            BlendFactor::Value(BlendValue::ConstantAlpha) => GL_CONSTANT_ALPHA,
+            BlendFactor::Value(BlendValue::Source1Color) => GL_SRC1_COLOR,
+            BlendFactor::Value(BlendValue::Source1Alpha) => GL_SRC1_ALPHA,
             BlendFactor::OneMinusValue(BlendValue::SourceColor) => GL_ONE_MINUS_SRC_COLOR,
             BlendFactor::OneMinusValue(BlendValue::SourceAlpha) => GL_ONE_MINUS_SRC_ALPHA,
             BlendFactor::OneMinusValue(BlendValue::DestinationColor) => GL_ONE_MINUS_DST_COLOR,
             BlendFactor::OneMinusValue(BlendValue::DestinationAlpha) => GL_ONE_MINUS_DST_ALPHA,
+            BlendFactor::OneMinusValue(BlendValue::ConstantColor) => GL_ONE_MINUS_CONSTANT_COLOR,
+            BlendFactor::OneMinusValue(BlendValue::ConstantAlpha) => GL_ONE_MINUS_CONSTANT_ALPHA,
+            BlendFactor::OneMinusValue(BlendValue::Source1Color) => GL_ONE_MINUS_SRC1_COLOR,
+            BlendFactor::OneMinusValue(BlendValue::Source1Alpha) => GL_ONE_MINUS_SRC1_ALPHA,
             BlendFactor::SourceAlphaSaturate => GL_SRC_ALPHA_SATURATE,
         }
     }
@@ -84,6 +94,17 @@ impl From<PrimitiveType> for GLenum {
     }
 }
 
+impl From<ConditionalRenderMode> for GLenum {
+    fn from(mode: ConditionalRenderMode) -> Self {
+        match mode {
+            ConditionalRenderMode::Wait => GL_QUERY_WAIT,
+            ConditionalRenderMode::NoWait => GL_QUERY_NO_WAIT,
+            ConditionalRenderMode::ByRegionWait => GL_QUERY_BY_REGION_WAIT,
+            ConditionalRenderMode::ByRegionNoWait => GL_QUERY_BY_REGION_NO_WAIT,
+        }
+    }
+}
+
 impl From<IndexType> for GLenum {
     fn from(index_type: IndexType) -> Self {
         match index_type {
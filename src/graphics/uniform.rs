@@ -29,6 +29,10 @@ pub enum UniformType {
     Int3,
     /// Four unsigned 32-bit integers (equivalent to `[u32; 4]`)
     Int4,
+    /// Two by two matrix of 32-bit floats
+    Mat2,
+    /// Three by three matrix of 32-bit floats, tightly packed (`[f32; 9]`), e.g. a normal matrix
+    Mat3,
     /// Four by four matrix of 32-bit floats
     Mat4,
 }
@@ -45,11 +49,41 @@ impl UniformType {
             UniformType::Int2 => 8,
             UniformType::Int3 => 12,
             UniformType::Int4 => 16,
+            UniformType::Mat2 => 16,
+            UniformType::Mat3 => 36,
             UniformType::Mat4 => 64,
         }
     }
 }
 
+impl ShaderUniform {
+    /// Upload `uniform_type` x `array_count` values read from `data`, no-op without a location.
+    pub(crate) unsafe fn apply(&self, data: *const u8) {
+        use UniformType::*;
+
+        let gl_loc = match self.gl_loc {
+            Some(gl_loc) => gl_loc,
+            None => return,
+        };
+        let data_float = data as *const f32;
+        let data_int = data as *const i32;
+
+        match self.uniform_type {
+            Float1 => glUniform1fv(gl_loc, self.array_count, data_float),
+            Float2 => glUniform2fv(gl_loc, self.array_count, data_float),
+            Float3 => glUniform3fv(gl_loc, self.array_count, data_float),
+            Float4 => glUniform4fv(gl_loc, self.array_count, data_float),
+            Int1 => glUniform1iv(gl_loc, self.array_count, data_int),
+            Int2 => glUniform2iv(gl_loc, self.array_count, data_int),
+            Int3 => glUniform3iv(gl_loc, self.array_count, data_int),
+            Int4 => glUniform4iv(gl_loc, self.array_count, data_int),
+            Mat2 => glUniformMatrix2fv(gl_loc, self.array_count, 0, data_float),
+            Mat3 => glUniformMatrix3fv(gl_loc, self.array_count, 0, data_float),
+            Mat4 => glUniformMatrix4fv(gl_loc, self.array_count, 0, data_float),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct UniformDesc {
     pub(crate) name: String,
@@ -51,4 +51,204 @@ mod tests {
 
         Ok(())
     }
+
+    /// Context on a hidden window, for tests that render offscreen
+    struct TestContext {
+        // dropped first, its GL objects are deleted while the window is alive
+        ctx: graphics::GraphicsContext,
+        _window: Box<glfw::Window>,
+        _glfw: glfw::Glfw,
+    }
+
+    fn test_context() -> Result<TestContext, Box<dyn std::error::Error>> {
+        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS)?;
+        glfw.window_hint(glfw::WindowHint::Visible(false));
+        let (window, _receiver) = glfw
+            .create_window(64, 64, "Test ", glfw::WindowMode::Windowed)
+            .ok_or("未能创建窗口")?;
+        // the context keeps a pointer to the window, it must not move afterwards
+        let mut window = Box::new(window);
+        window.make_current();
+
+        Ok(TestContext {
+            ctx: window.create_context(),
+            _window: window,
+            _glfw: glfw,
+        })
+    }
+
+    /// Shader of a quad given in clip space, with `fragment` sampling `images`
+    fn fullscreen_shader(
+        ctx: &mut graphics::GraphicsContext,
+        fragment: &str,
+        images: &[&str],
+    ) -> Result<shader::Shader, shader::ShaderError> {
+        shader::Shader::new(
+            ctx,
+            "attribute vec2 pos; void main() { gl_Position = vec4(pos, 0.0, 1.0); }",
+            fragment,
+            shader::ShaderMeta {
+                uniforms: uniform::UniformBlockLayout { uniforms: vec![] },
+                images: images.iter().map(|name| name.to_string()).collect(),
+            },
+        )
+    }
+
+    fn fullscreen_pipeline(
+        ctx: &mut graphics::GraphicsContext,
+        shader: shader::Shader,
+    ) -> pipeline::Pipeline {
+        pipeline::Pipeline::new(
+            ctx,
+            &[pipeline::BufferLayout::default()],
+            &[pipeline::VertexAttribute::new(
+                "pos",
+                pipeline::VertexFormat::Float2,
+            )],
+            shader,
+        )
+    }
+
+    /// Draw a quad covering `target` with `fragment`, sampling `images`
+    fn draw_fullscreen(
+        ctx: &mut graphics::GraphicsContext,
+        target: pass::RenderPass,
+        fragment: &str,
+        images: Vec<(&str, Texture)>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let names: Vec<&str> = images.iter().map(|(name, _)| *name).collect();
+        let shader = fullscreen_shader(ctx, fragment, &names)?;
+        let pipeline = fullscreen_pipeline(ctx, shader);
+        let vertices: [[f32; 2]; 4] = [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]];
+        let bindings = buffer::Bindings {
+            vertex_buffers: vec![buffer::Buffer::immutable(
+                ctx,
+                buffer::BufferType::VertexBuffer,
+                &vertices,
+            )],
+            vertex_buffer_offsets: vec![],
+            index_buffer: buffer::Buffer::quad_indices(ctx, 1),
+            images: images.into_iter().map(|(_, texture)| texture).collect(),
+        };
+
+        ctx.begin_pass(target, pass::PassAction::Nothing);
+        ctx.apply_pipeline(&pipeline);
+        ctx.apply_bindings(&bindings);
+        ctx.draw(0, 6, 1);
+        ctx.end_render_pass();
+        Ok(())
+    }
+
+    fn render_target(
+        ctx: &mut graphics::GraphicsContext,
+        width: u32,
+        height: u32,
+    ) -> pass::RenderPass {
+        let target = Texture::new_render_texture(
+            ctx,
+            TextureParams {
+                width,
+                height,
+                ..Default::default()
+            },
+        );
+        pass::RenderPass::new(ctx, target, None)
+    }
+
+    #[test]
+    fn pipeline_keeps_shader_alive() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test = test_context()?;
+        let ctx = &mut test.ctx;
+
+        let shader = fullscreen_shader(ctx, "void main() { gl_FragColor = vec4(1.0); }", &[])?;
+        let pipeline = fullscreen_pipeline(ctx, shader);
+
+        assert!(matches!(
+            shader.delete(ctx),
+            Err(shader::ShaderError::InUse(1))
+        ));
+        ctx.apply_pipeline(&pipeline);
+
+        pipeline.delete(ctx);
+        shader.delete(ctx)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn clamp_samples_edge_texels() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test = test_context()?;
+        let ctx = &mut test.ctx;
+
+        let red_green = [255, 0, 0, 255, 0, 255, 0, 255];
+        let texture = Texture::from_data_and_format(
+            ctx,
+            &red_green,
+            TextureParams {
+                format: TextureFormat::RGBA8,
+                wrap: TextureWrap::Clamp,
+                filter: FilterMode::Nearest,
+                width: 2,
+                height: 1,
+                mipmap: false,
+                border_color: [0.; 4],
+            },
+        );
+        let pass = render_target(ctx, 4, 1);
+
+        // fragments at x = 0.5..3.5 sample u = -0.25, 0.25, 0.75, 1.25
+        draw_fullscreen(
+            ctx,
+            pass,
+            "uniform sampler2D tex; void main() { gl_FragColor = texture2D(tex, vec2(gl_FragCoord.x / 2.0 - 0.5, 0.5)); }",
+            vec![("tex", texture)],
+        )?;
+
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        assert_eq!(pass.read_pixel(ctx, 0, 0), red);
+        assert_eq!(pass.read_pixel(ctx, 1, 0), red);
+        assert_eq!(pass.read_pixel(ctx, 2, 0), green);
+        assert_eq!(pass.read_pixel(ctx, 3, 0), green);
+
+        Ok(())
+    }
+
+    #[test]
+    fn texture_read_pixels_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test = test_context()?;
+        let ctx = &mut test.ctx;
+
+        let pixels: Vec<u8> = (0..2 * 3 * 4).map(|i| i as u8 * 10).collect();
+        let texture = Texture::from_rgba8(ctx, 2, 3, &pixels);
+
+        let mut read_back = vec![0; pixels.len()];
+        texture.read_pixels(ctx, &mut read_back);
+        assert_eq!(read_back, pixels);
+
+        Ok(())
+    }
+
+    #[test]
+    fn texture_clone_outlives_original() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test = test_context()?;
+        let ctx = &mut test.ctx;
+
+        let texture = Texture::from_rgba8(ctx, 1, 1, &[0, 0, 255, 255]);
+        let clone = texture.clone();
+        drop(texture);
+
+        let pass = render_target(ctx, 1, 1);
+        draw_fullscreen(
+            ctx,
+            pass,
+            "uniform sampler2D tex; void main() { gl_FragColor = texture2D(tex, vec2(0.5, 0.5)); }",
+            vec![("tex", clone)],
+        )?;
+
+        assert_eq!(pass.read_pixel(ctx, 0, 0), [0, 0, 255, 255]);
+        ctx.check_errors()?;
+
+        Ok(())
+    }
 }